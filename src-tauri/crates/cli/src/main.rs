@@ -0,0 +1,373 @@
+//! Command-line front end for scripts: same search, evaluation, keyring
+//! token and listing cache as the desktop app.
+
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+
+use instances_finder_core::advisories::AdvisoryList;
+use instances_finder_core::model::{FetchParams, JsInstance};
+use instances_finder_core::probe::ProbeClient;
+use instances_finder_core::score::{self, Preferences};
+use instances_finder_core::token::{self, TOKEN_ENV};
+use instances_finder_core::uptime::UptimeStore;
+use instances_finder_core::{directory, evaluate, storage};
+
+const SORTS: [&str; 4] = ["relevance", "activity", "growth", "peers"];
+
+// Messages stay in English here, like the rest of the output
+type CliResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Parser)]
+#[command(
+    name = "instances-finder-cli",
+    version,
+    about = "Find Mastodon instances"
+)]
+struct Cli {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Output::Table, global = true)]
+    output: Output,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Output {
+    Table,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Search the instances.social directory and rank the matches
+    Search(SearchArgs),
+    /// List the languages found in the directory
+    Languages,
+    /// Evaluate servers directly, without the directory
+    Evaluate {
+        #[arg(required = true)]
+        domains: Vec<String>,
+    },
+    /// Manage the instances.social token stored in the system keyring
+    Token {
+        #[command(subcommand)]
+        action: TokenAction,
+    },
+    /// Manage the directory listing cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum TokenAction {
+    /// Store a token; read from standard input when omitted
+    Set { token: Option<String> },
+    /// Remove the stored token
+    Clear,
+    /// Check the token against instances.social
+    Test,
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Delete the cached listing
+    Clear,
+    /// Show what the cache holds
+    Stats,
+}
+
+/// Same filters as the desktop search (`FetchParams`).
+#[derive(Args)]
+struct SearchArgs {
+    /// Language code(s), e.g. "fr" or "fr,en"
+    #[arg(long)]
+    language: Option<String>,
+    /// Include servers with closed sign-ups
+    #[arg(long)]
+    include_closed: bool,
+    /// Include servers currently down
+    #[arg(long)]
+    include_down: bool,
+    /// Directory entries to fetch (default 200)
+    #[arg(long)]
+    max: Option<usize>,
+    #[arg(long, value_parser = ["open", "approval"])]
+    signups: Option<String>,
+    #[arg(long, value_parser = ["eu", "na", "other"])]
+    region: Option<String>,
+    #[arg(long, value_parser = ["small", "medium", "large"])]
+    size: Option<String>,
+    /// NodeInfo software name, e.g. "glitch-soc"
+    #[arg(long)]
+    software: Option<String>,
+    /// Lowest accepted security grade
+    #[arg(long, value_parser = ["A", "B", "C", "D", "E", "F"])]
+    min_security: Option<String>,
+    /// Skip servers fronted by a CDN
+    #[arg(long)]
+    exclude_cdn: bool,
+    /// Skip servers whose pages load third-party resources
+    #[arg(long)]
+    exclude_third_party: bool,
+    /// Hide versions with unpatched critical advisories
+    #[arg(long)]
+    hide_vulnerable: bool,
+    /// Look for shutdown, migration or read-only notices
+    #[arg(long)]
+    check_notices: bool,
+    /// Read peers and weekly activity
+    #[arg(long)]
+    check_activity: bool,
+    #[arg(long, default_value = "relevance", value_parser = SORTS)]
+    sort: String,
+    /// Ignore the listing cache
+    #[arg(long)]
+    no_cache: bool,
+}
+
+impl SearchArgs {
+    fn params(&self) -> FetchParams {
+        let flag = |set: bool| set.then_some(true);
+        FetchParams {
+            language: self.language.clone(),
+            include_closed: flag(self.include_closed),
+            include_down: flag(self.include_down),
+            max: self.max,
+            signups: self.signups.clone(),
+            region: self.region.clone(),
+            size: self.size.clone(),
+            software: self.software.clone(),
+            min_security: self.min_security.clone(),
+            exclude_cdn: flag(self.exclude_cdn),
+            exclude_third_party: flag(self.exclude_third_party),
+            hide_vulnerable: flag(self.hide_vulnerable),
+            check_notices: flag(self.check_notices),
+            check_activity: flag(self.check_activity),
+        }
+    }
+
+    // Rank with the wizard preferences these filters correspond to
+    fn preferences(&self) -> Preferences {
+        let any = |v: &Option<String>| v.clone().unwrap_or_else(|| "any".into());
+        let privacy = match (self.exclude_cdn, self.exclude_third_party) {
+            (true, true) => "strict",
+            (true, false) => "no_cdn",
+            (false, true) => "no_third_party",
+            (false, false) => "any",
+        };
+        Preferences {
+            languages: self
+                .language
+                .iter()
+                .flat_map(|l| l.split(','))
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .collect(),
+            size: any(&self.size),
+            signups: any(&self.signups),
+            region: any(&self.region),
+            software: any(&self.software),
+            security: any(&self.min_security),
+            privacy: privacy.into(),
+            vulnerable: if self.hide_vulnerable { "hide" } else { "any" }.into(),
+            sort: self.sort.clone(),
+            ..Preferences::default()
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn data_file(name: &str) -> PathBuf {
+    storage::data_file(&storage::default_app_data_dir(), name)
+}
+
+fn stored_token() -> Result<String, String> {
+    token::find().ok_or_else(|| {
+        format!("no token available; run `instances-finder-cli token set` or set {TOKEN_ENV}")
+    })
+}
+
+fn run(cli: Cli) -> CliResult<()> {
+    let output = cli.output;
+    match cli.command {
+        Command::Search(args) => {
+            let params = args.params();
+            let cache = data_file(storage::CACHE_FILE);
+            let cached = if args.no_cache {
+                None
+            } else {
+                directory::read_cache(&cache, &params)
+            };
+            let mut items = match cached {
+                Some(items) => items,
+                None => {
+                    let advisories = AdvisoryList::load(&data_file(storage::ADVISORIES_FILE));
+                    let items = directory::search(&stored_token()?, &params, &advisories)?;
+                    directory::write_cache(&cache, &params, &items);
+                    items
+                }
+            };
+            score::rank_instances(&mut items, &args.preferences());
+            print_instances(&items, output)
+        }
+        Command::Languages => {
+            let languages = directory::languages(&stored_token()?)?;
+            match output {
+                Output::Json => print_json(&languages),
+                Output::Table => {
+                    languages.iter().for_each(|l| println!("{l}"));
+                    Ok(())
+                }
+            }
+        }
+        Command::Evaluate { domains } => evaluate_domains(&domains, output),
+        Command::Token { action } => match action {
+            TokenAction::Set { token } => {
+                let token = match token {
+                    Some(t) => t,
+                    None => io::stdin()
+                        .lock()
+                        .lines()
+                        .next()
+                        .transpose()?
+                        .unwrap_or_default(),
+                };
+                let token = token.trim();
+                if token.is_empty() {
+                    return Err("empty token".into());
+                }
+                token::store(token)?;
+                println!("Token saved.");
+                Ok(())
+            }
+            TokenAction::Clear => {
+                token::delete();
+                println!("Token removed.");
+                Ok(())
+            }
+            TokenAction::Test => {
+                directory::test_token(&stored_token()?)?;
+                println!("Token accepted by instances.social.");
+                Ok(())
+            }
+        },
+        Command::Cache { action } => {
+            let cache = data_file(storage::CACHE_FILE);
+            match action {
+                CacheAction::Clear => {
+                    directory::clear_cache(&cache)?;
+                    println!("Cache cleared.");
+                    Ok(())
+                }
+                CacheAction::Stats => {
+                    let info = directory::cache_info(&cache);
+                    match (output, info) {
+                        (Output::Json, info) => print_json(&info),
+                        (Output::Table, None) => {
+                            println!("No cached listing.");
+                            Ok(())
+                        }
+                        (Output::Table, Some(info)) => {
+                            println!("Path: {}", cache.display());
+                            println!("Instances: {}", info.items);
+                            println!("Age: {} min", info.age_secs / 60);
+                            Ok(())
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn evaluate_domains(domains: &[String], output: Output) -> CliResult<()> {
+    let advisories = AdvisoryList::load(&data_file(storage::ADVISORIES_FILE));
+    let uptime = UptimeStore::new(&data_file(storage::UPTIME_FILE));
+    let mut items = Vec::new();
+    let mut failed = 0;
+    for domain in domains {
+        let result = ProbeClient::new(domain).and_then(|client| {
+            let _ = uptime.record(client.domain(), &client.ping());
+            evaluate::evaluate(&client, &advisories)
+        });
+        match result {
+            Ok(item) => items.push(item),
+            Err(e) => {
+                eprintln!("{domain}: {e}");
+                failed += 1;
+            }
+        }
+    }
+    print_instances(&items, output)?;
+    if failed > 0 {
+        return Err(format!("{failed} of {} domains failed", domains.len()).into());
+    }
+    Ok(())
+}
+
+fn print_json<T: Serialize>(value: &T) -> CliResult<()> {
+    let text = serde_json::to_string_pretty(value)?;
+    println!("{text}");
+    Ok(())
+}
+
+fn print_instances(items: &[JsInstance], output: Output) -> CliResult<()> {
+    if output == Output::Json {
+        return print_json(&items);
+    }
+    let headers = ["#", "Domain", "Sign-ups", "Size", "Users", "Languages"];
+    let rows: Vec<[String; 6]> = items
+        .iter()
+        .enumerate()
+        .map(|(idx, it)| {
+            [
+                (idx + 1).to_string(),
+                it.domain.clone(),
+                it.signups.clone(),
+                match it.size {
+                    1 => "small",
+                    2 => "medium",
+                    _ => "large",
+                }
+                .to_string(),
+                it.users.map(|u| u.to_string()).unwrap_or_default(),
+                it.languages.join(","),
+            ]
+        })
+        .collect();
+    let mut widths = headers.map(|h| h.chars().count());
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(c, w)| format!("{c:<w$}"))
+            .collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(&headers.map(String::from));
+    for row in &rows {
+        line(row);
+    }
+    Ok(())
+}
@@ -0,0 +1,212 @@
+//! Alt text and hashtag habits sampled from the public local timeline.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::html;
+use crate::moderation;
+use crate::probe::ProbeClient;
+
+const SAMPLE_SIZE: u32 = 40;
+
+// Lowercase phrases (EN/FR) showing that an instance cares about accessibility
+const ACCESSIBILITY_KEYWORDS: &[&str] = &[
+    "alt text",
+    "alt-text",
+    "alttext",
+    "image description",
+    "accessibility",
+    "accessible",
+    "screen reader",
+    "camelcase",
+    "texte alternatif",
+    "description d'image",
+    "descriptions d'image",
+    "accessibilité",
+    "lecteur d'écran",
+    "lecteurs d'écran",
+];
+
+/// Accessibility signals measured on the public local timeline.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilityReport {
+    /// False when the instance does not expose its local timeline anonymously.
+    pub timeline_public: bool,
+    pub sampled_posts: usize,
+    pub media_count: usize,
+    pub media_with_alt: usize,
+    pub alt_text_ratio: Option<f32>,
+    pub hashtag_count: usize,
+    pub camel_case_ratio: Option<f32>,
+    /// Rules or descriptions mention alt text or accessibility.
+    pub mentions_accessibility: bool,
+    /// 0..1, only when media alt text could be measured.
+    pub score: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Status {
+    content: String,
+    #[serde(default)]
+    media_attachments: Vec<MediaAttachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaAttachment {
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstanceV2 {
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtendedDescription {
+    #[serde(default)]
+    content: String,
+}
+
+pub fn accessibility_report(client: &ProbeClient) -> Result<AccessibilityReport, Error> {
+    // Admins can restrict the public timeline to logged-in users: respect
+    // that and only rely on rules and descriptions then.
+    let statuses: Option<Vec<Status>> = client.get_public(
+        "/api/v1/timelines/public",
+        &[("local", "true".into()), ("limit", SAMPLE_SIZE.to_string())],
+    )?;
+    let timeline_public = statuses.is_some();
+    let statuses = statuses.unwrap_or_default();
+
+    let media: Vec<&MediaAttachment> = statuses
+        .iter()
+        .flat_map(|s| s.media_attachments.iter())
+        .collect();
+    let media_with_alt = media
+        .iter()
+        .filter(|m| {
+            m.description
+                .as_deref()
+                .is_some_and(|d| !d.trim().is_empty())
+        })
+        .count();
+
+    let tags: Vec<String> = statuses
+        .iter()
+        .flat_map(|s| hashtags(&html::to_plain_text(&s.content)))
+        .collect();
+    let camel_tags = tags.iter().filter(|t| is_camel_case(t)).count();
+
+    let alt_text_ratio = ratio(media_with_alt, media.len());
+    let camel_case_ratio = ratio(camel_tags, tags.len());
+    let mentions_accessibility = mentions_accessibility(client)?;
+
+    let score = alt_text_ratio.map(|alt| {
+        let camel = camel_case_ratio.unwrap_or(0.5);
+        let mention = if mentions_accessibility { 1.0 } else { 0.0 };
+        alt * 0.6 + camel * 0.2 + mention * 0.2
+    });
+
+    Ok(AccessibilityReport {
+        timeline_public,
+        sampled_posts: statuses.len(),
+        media_count: media.len(),
+        media_with_alt,
+        alt_text_ratio,
+        hashtag_count: tags.len(),
+        camel_case_ratio,
+        mentions_accessibility,
+        score,
+    })
+}
+
+fn mentions_accessibility(client: &ProbeClient) -> Result<bool, Error> {
+    let mut texts: Vec<String> = moderation::fetch_rules(client)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|r| r.text)
+        .collect();
+    if let Some(instance) = client.get_public::<InstanceV2>("/api/v2/instance", &[])? {
+        texts.push(instance.description);
+    }
+    if let Some(extended) =
+        client.get_public::<ExtendedDescription>("/api/v1/instance/extended_description", &[])?
+    {
+        texts.push(html::to_plain_text(&extended.content));
+    }
+    Ok(texts.iter().any(|t| {
+        let t = t.to_lowercase().replace('\u{2019}', "'");
+        ACCESSIBILITY_KEYWORDS.iter().any(|k| t.contains(k))
+    }))
+}
+
+fn ratio(part: usize, total: usize) -> Option<f32> {
+    (total > 0).then(|| part as f32 / total as f32)
+}
+
+/// Hashtags (without `#`) found in a plain-text post.
+fn hashtags(text: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at_boundary = prev.is_none_or(|p| !(p.is_alphanumeric() || p == '_'));
+        if c == '#' && at_boundary {
+            let start = i + 1;
+            let mut end = start;
+            while let Some(&(j, n)) = chars.peek() {
+                if !(n.is_alphanumeric() || n == '_') {
+                    break;
+                }
+                end = j + n.len_utf8();
+                chars.next();
+            }
+            let tag = &text[start..end];
+            if tag.chars().any(char::is_alphabetic) {
+                tags.push(tag.to_string());
+            }
+            prev = tag.chars().last().or(Some(c));
+            continue;
+        }
+        prev = Some(c);
+    }
+    tags
+}
+
+/// `#AltText` or `#altText`: an uppercase letter right after a lowercase one
+/// or a digit, which lets screen readers split the words.
+fn is_camel_case(tag: &str) -> bool {
+    tag.chars()
+        .zip(tag.chars().skip(1))
+        .any(|(a, b)| (a.is_lowercase() || a.is_ascii_digit()) && b.is_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_hashtags_at_word_boundaries() {
+        assert_eq!(
+            hashtags("#AltText and (#accessibilité), #a11y."),
+            ["AltText", "accessibilité", "a11y"]
+        );
+        // Not tags: inside a word, numbers only, a bare sign
+        assert!(hashtags("issue#12 C# # #2024").is_empty());
+        assert_eq!(hashtags("#snake_case#glued"), ["snake_case"]);
+        assert!(hashtags("").is_empty());
+    }
+
+    #[test]
+    fn camel_case_needs_a_capital_after_lowercase_or_digit() {
+        assert!(is_camel_case("AltText"));
+        assert!(is_camel_case("altText"));
+        assert!(is_camel_case("Web3Dev"));
+        assert!(!is_camel_case("alttext"));
+        assert!(!is_camel_case("ALTTEXT"));
+        assert!(!is_camel_case("Alttext"));
+        assert!(!is_camel_case("alt_text"));
+        assert!(!is_camel_case(""));
+    }
+}
@@ -0,0 +1,176 @@
+//! Federation peers and weekly activity from the Mastodon API.
+
+use std::fmt;
+
+use serde::de::{Deserializer, IgnoredAny, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::probe::ProbeClient;
+
+// Weeks averaged on each side of the trend comparison
+const TREND_WEEKS: usize = 4;
+// Below this change in weekly logins the instance is called stable
+const STABLE_BAND: f32 = 0.1;
+
+/// How alive and connected an instance is.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityReport {
+    /// Known federated servers, when the instance shares its peers.
+    pub peers: Option<usize>,
+    /// Weekly averages over the complete weeks returned (up to 11).
+    pub weekly_statuses: Option<f32>,
+    pub weekly_logins: Option<f32>,
+    pub weekly_registrations: Option<f32>,
+    /// Relative change of weekly logins, last 4 weeks against the 4
+    /// before (0.25 = +25 %).
+    pub trend: Option<f32>,
+    pub trend_label: String, // "growing" | "stable" | "declining" | "unknown"
+    /// Weekly logins per registered account (0..1).
+    pub logins_per_user: Option<f32>,
+}
+
+// Mastodon serializes these counters as strings
+#[derive(Debug, Deserialize)]
+struct ActivityWeek {
+    statuses: String,
+    logins: String,
+    registrations: String,
+}
+
+// Length of a JSON array, read without keeping its items: a large
+// instance lists tens of thousands of peers
+struct Count(usize);
+
+impl<'de> Deserialize<'de> for Count {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CountVisitor;
+
+        impl<'de> Visitor<'de> for CountVisitor {
+            type Value = Count;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an array")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Count, A::Error> {
+                let mut count = 0;
+                while seq.next_element::<IgnoredAny>()?.is_some() {
+                    count += 1;
+                }
+                Ok(Count(count))
+            }
+        }
+
+        deserializer.deserialize_seq(CountVisitor)
+    }
+}
+
+struct Week {
+    statuses: f32,
+    logins: f32,
+    registrations: f32,
+}
+
+/// Read peers and weekly activity; `users` (registered accounts, from the
+/// directory or NodeInfo) turns logins into a per-user ratio.
+pub fn activity_report(client: &ProbeClient, users: Option<u64>) -> Result<ActivityReport, Error> {
+    let peers = client
+        .get_public::<Count>("/api/v1/instance/peers", &[])?
+        .map(|Count(n)| n);
+    let weeks: Vec<Week> = client
+        .get_public::<Vec<ActivityWeek>>("/api/v1/instance/activity", &[])?
+        .unwrap_or_default()
+        .into_iter()
+        // Newest first; the current week is still running
+        .skip(1)
+        .filter_map(|w| {
+            Some(Week {
+                statuses: w.statuses.parse().ok()?,
+                logins: w.logins.parse().ok()?,
+                registrations: w.registrations.parse().ok()?,
+            })
+        })
+        .collect();
+
+    let weekly_logins = average(weeks.iter().map(|w| w.logins));
+    let trend = trend(&weeks);
+    let trend_label = match trend {
+        Some(t) if t > STABLE_BAND => "growing",
+        Some(t) if t < -STABLE_BAND => "declining",
+        Some(_) => "stable",
+        None => "unknown",
+    };
+    let logins_per_user = weekly_logins
+        .zip(users.filter(|u| *u > 0))
+        .map(|(logins, users)| (logins / users as f32).min(1.0));
+
+    Ok(ActivityReport {
+        peers,
+        weekly_statuses: average(weeks.iter().map(|w| w.statuses)),
+        weekly_logins,
+        weekly_registrations: average(weeks.iter().map(|w| w.registrations)),
+        trend,
+        trend_label: trend_label.into(),
+        logins_per_user,
+    })
+}
+
+fn average(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, count) = values.fold((0.0, 0), |(s, n), v| (s + v, n + 1));
+    (count > 0).then(|| sum / count as f32)
+}
+
+fn trend(weeks: &[Week]) -> Option<f32> {
+    if weeks.len() < TREND_WEEKS * 2 {
+        return None;
+    }
+    let recent = average(weeks[..TREND_WEEKS].iter().map(|w| w.logins))?;
+    let before = average(weeks[TREND_WEEKS..TREND_WEEKS * 2].iter().map(|w| w.logins))?;
+    if before == 0.0 {
+        return (recent > 0.0).then_some(1.0);
+    }
+    Some((recent - before) / before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Newest first, like the API
+    fn weeks(logins: &[f32]) -> Vec<Week> {
+        logins
+            .iter()
+            .map(|&logins| Week {
+                statuses: 0.0,
+                logins,
+                registrations: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compares_the_last_four_weeks_with_the_four_before() {
+        let growing = weeks(&[150.0, 150.0, 150.0, 150.0, 100.0, 100.0, 100.0, 100.0]);
+        assert_eq!(trend(&growing), Some(0.5));
+        let declining = weeks(&[50.0, 50.0, 50.0, 50.0, 100.0, 100.0, 100.0, 100.0]);
+        assert_eq!(trend(&declining), Some(-0.5));
+        // Older weeks are left out
+        let longer = weeks(&[10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 500.0]);
+        assert_eq!(trend(&longer), Some(0.0));
+    }
+
+    #[test]
+    fn needs_eight_weeks() {
+        assert_eq!(trend(&weeks(&[1.0; 7])), None);
+        assert_eq!(trend(&[]), None);
+    }
+
+    #[test]
+    fn activity_after_silence_counts_as_growth() {
+        let woke_up = weeks(&[4.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(trend(&woke_up), Some(1.0));
+        assert_eq!(trend(&weeks(&[0.0; 8])), None);
+    }
+}
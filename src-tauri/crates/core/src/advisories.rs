@@ -0,0 +1,221 @@
+//! Known Mastodon security advisories and version checks.
+
+use std::{cmp::Ordering, fs, path::Path, time::SystemTime};
+
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+
+// Mastodon security releases known at build time; a newer list can be
+// imported into the app data dir without a new release.
+const BUNDLED: &str = include_str!("../data/advisories.json");
+
+// Software whose versions follow Mastodon's
+const MASTODON_FAMILY: &[&str] = &["mastodon", "glitch-soc", "hometown"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AdvisoryList {
+    /// ISO date (`YYYY-MM-DD`) of the last change to the list.
+    pub updated: String,
+    pub advisories: Vec<Advisory>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Advisory {
+    pub id: String,
+    pub title: String,
+    pub severity: String, // "low" | "medium" | "high" | "critical"
+    pub published: String,
+    /// First patched version of each maintained branch.
+    pub fixed: Vec<String>,
+}
+
+/// Advisories an instance has not applied yet.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VulnerabilityReport {
+    pub version: String,
+    pub advisories: Vec<Advisory>,
+    /// Highest severity among `advisories`.
+    pub severity: String,
+    /// Days since the oldest critical advisory was published, if any.
+    pub critical_unpatched_days: Option<u64>,
+}
+
+impl AdvisoryList {
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED).expect("bundled advisories.json is valid")
+    }
+
+    /// The imported list when it is readable and at least as recent as the
+    /// bundled one, the bundled list otherwise.
+    pub fn load(imported: &Path) -> Self {
+        let bundled = Self::bundled();
+        fs::read(imported)
+            .ok()
+            .and_then(|bytes| Self::parse(&bytes).ok())
+            .filter(|list| !list.is_older_than(&bundled))
+            .unwrap_or(bundled)
+    }
+
+    /// Compares the `updated` dates, which may lack zero padding.
+    pub fn is_older_than(&self, other: &AdvisoryList) -> bool {
+        days_from_iso(&self.updated) < days_from_iso(&other.updated)
+    }
+
+    /// Days since `updated`, `None` for an unreadable date.
+    pub fn age_days(&self) -> Option<u64> {
+        days_from_iso(&self.updated).map(|updated| today().saturating_sub(updated))
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        let list: Self = serde_json::from_slice(bytes)?;
+        if days_from_iso(&list.updated).is_none() {
+            return Err(Error::AdvisoryDate { date: list.updated });
+        }
+        for a in &list.advisories {
+            if severity_rank(&a.severity) == 0 {
+                return Err(Error::AdvisorySeverity {
+                    id: a.id.clone(),
+                    severity: a.severity.clone(),
+                });
+            }
+            if a.fixed.is_empty() || a.fixed.iter().any(|v| Version::parse(v).is_none()) {
+                return Err(Error::AdvisoryVersions { id: a.id.clone() });
+            }
+        }
+        Ok(list)
+    }
+
+    /// Compare a reported version against the list; `None` when the
+    /// software is not Mastodon-based, the version unreadable or patched.
+    pub fn check(&self, software: &str, version: &str) -> Option<VulnerabilityReport> {
+        if !MASTODON_FAMILY.contains(&software.trim().to_lowercase().as_str()) {
+            return None;
+        }
+        let running = Version::parse(version)?;
+        let advisories: Vec<Advisory> = self
+            .advisories
+            .iter()
+            .filter(|a| affects(a, &running))
+            .cloned()
+            .collect();
+        let severity = advisories
+            .iter()
+            .map(|a| a.severity.as_str())
+            .max_by_key(|s| severity_rank(s))?
+            .to_string();
+        let today = today();
+        let critical_unpatched_days = advisories
+            .iter()
+            .filter(|a| a.severity == "critical")
+            .filter_map(|a| days_from_iso(&a.published))
+            .min()
+            .map(|published| today.saturating_sub(published));
+        Some(VulnerabilityReport {
+            version: version.trim().to_string(),
+            advisories,
+            severity,
+            critical_unpatched_days,
+        })
+    }
+}
+
+pub fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "low" => 1,
+        "medium" => 2,
+        "high" => 3,
+        "critical" => 4,
+        _ => 0,
+    }
+}
+
+/// Vulnerable when the branch's fix is not applied, or when the branch
+/// got no fix at all while a newer one did (end-of-life branches).
+fn affects(advisory: &Advisory, running: &Version) -> bool {
+    let fixed: Vec<Version> = advisory
+        .fixed
+        .iter()
+        .filter_map(|v| Version::parse(v))
+        .collect();
+    if let Some(same_branch) = fixed.iter().find(|f| f.branch() == running.branch()) {
+        return running < same_branch;
+    }
+    fixed.iter().any(|f| running < f)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Version {
+    major: u32,
+    minor: u32,
+    patch: u32,
+    prerelease: bool,
+}
+
+impl Version {
+    /// Reads `4.2.8`, `v4.2.8`, `4.3.0-beta.1`, `4.2.8+glitch`,
+    /// `4.2.8+hometown-1.1.1`; build metadata is ignored.
+    fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim().trim_start_matches('v');
+        let core = raw.split('+').next()?;
+        let (numbers, prerelease) = match core.split_once('-') {
+            Some((numbers, _)) => (numbers, true),
+            None => (core, false),
+        };
+        let mut parts = numbers.split('.').map(|p| p.parse::<u32>());
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+        let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+        Some(Self {
+            major,
+            minor,
+            patch,
+            prerelease,
+        })
+    }
+
+    fn branch(&self) -> (u32, u32) {
+        (self.major, self.minor)
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch, !self.prerelease).cmp(&(
+            other.major,
+            other.minor,
+            other.patch,
+            !other.prerelease,
+        ))
+    }
+}
+
+fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or_default()
+}
+
+/// Days since the Unix epoch of a `YYYY-MM-DD` date.
+fn days_from_iso(date: &str) -> Option<u64> {
+    let mut parts = date.trim().splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (y, m, d) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+    // Howard Hinnant's days_from_civil
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    u64::try_from(era * 146_097 + doe - 719_468).ok()
+}
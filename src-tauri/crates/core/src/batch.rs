@@ -0,0 +1,166 @@
+//! Evaluate a list of domains pasted by the user or read from a file.
+
+use std::{
+    fs,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::advisories::AdvisoryList;
+use crate::error::Error;
+use crate::evaluate;
+use crate::model::JsInstance;
+use crate::probe::{self, ProbeClient};
+use crate::score::{self, Preferences};
+
+const BATCH_WORKERS: usize = 6;
+const HOST_BUDGET_SECS: u64 = 45;
+const MAX_LIST_BYTES: u64 = 1024 * 1024;
+
+/// Where the domain list comes from.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum DomainListSource {
+    /// Pasted text, one domain per line (commas and spaces also split).
+    Text { text: String },
+    /// A `.txt` or `.csv` file; `None` asks the user with a file dialog.
+    File { path: Option<String> },
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BatchError {
+    pub domain: String,
+    /// `error` in English, until the caller relabels it.
+    pub reason: String,
+    #[serde(skip)]
+    pub error: Error,
+}
+
+impl BatchError {
+    fn new(domain: &str, error: Error) -> Self {
+        Self {
+            domain: domain.to_string(),
+            reason: error.to_string(),
+            error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BatchResult {
+    /// Ranked like directory results.
+    pub items: Vec<JsInstance>,
+    pub errors: Vec<BatchError>,
+}
+
+/// Emitted after each host, successful or not.
+#[derive(Debug, Serialize, Clone)]
+pub struct BatchProgress {
+    pub done: usize,
+    pub total: usize,
+    pub domain: String,
+    pub ok: bool,
+}
+
+pub fn read_domain_file(path: &Path) -> Result<String, Error> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    if !matches!(ext.as_deref(), Some("txt" | "csv")) {
+        return Err(Error::UnsupportedFile {
+            path: path.display().to_string(),
+        });
+    }
+    let meta = fs::metadata(path)?;
+    if meta.len() > MAX_LIST_BYTES {
+        return Err(Error::FileTooLarge {
+            path: path.display().to_string(),
+        });
+    }
+    Ok(fs::read_to_string(path)?)
+}
+
+/// Extract domains from a text or CSV list.
+///
+/// Blank lines and `#` comments are skipped; in each row the first field
+/// that is a valid hostname wins, so CSV exports with extra columns (and a
+/// header row) work as-is. Rows without any hostname are reported.
+pub fn parse_domain_list(text: &str) -> (Vec<String>, Vec<BatchError>) {
+    let mut domains: Vec<String> = Vec::new();
+    let mut errors = Vec::new();
+    let mut first_row = true;
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let header_allowed = std::mem::replace(&mut first_row, false);
+        let fields: Vec<&str> = line
+            .split([',', ';', '\t', ' '])
+            .map(|f| f.trim().trim_matches('"'))
+            .filter(|f| !f.is_empty())
+            .collect();
+        let found: Vec<String> = fields
+            .iter()
+            .filter_map(|f| probe::normalize_domain(f).ok())
+            .collect();
+        if found.is_empty() {
+            // Most likely a CSV header
+            if !header_allowed {
+                let error = Error::InvalidDomain {
+                    domain: line.to_string(),
+                };
+                errors.push(BatchError::new(line, error));
+            }
+            continue;
+        }
+        // Pasted text may hold several domains per line; CSV rows one
+        let take = if fields.len() == found.len() {
+            found.len()
+        } else {
+            1
+        };
+        for d in found.into_iter().take(take) {
+            if !domains.contains(&d) {
+                domains.push(d);
+            }
+        }
+    }
+    (domains, errors)
+}
+
+/// Evaluate every domain on a bounded worker pool, then rank the results.
+pub fn evaluate_domains(
+    domains: &[String],
+    prefs: &Preferences,
+    advisories: &AdvisoryList,
+    on_progress: &(dyn Fn(BatchProgress) + Sync),
+) -> (Vec<JsInstance>, Vec<BatchError>) {
+    let done = AtomicUsize::new(0);
+    let results = probe::parallel_map(domains, BATCH_WORKERS, |domain| {
+        let result = ProbeClient::with_deadline(domain, Duration::from_secs(HOST_BUDGET_SECS))
+            .and_then(|client| evaluate::evaluate(&client, advisories));
+        on_progress(BatchProgress {
+            done: done.fetch_add(1, Ordering::Relaxed) + 1,
+            total: domains.len(),
+            domain: domain.clone(),
+            ok: result.is_ok(),
+        });
+        result
+    });
+
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (domain, result) in domains.iter().zip(results) {
+        match result {
+            Ok(item) => items.push(item),
+            Err(error) => errors.push(BatchError::new(domain, error)),
+        }
+    }
+    score::rank_instances(&mut items, prefs);
+    (items, errors)
+}
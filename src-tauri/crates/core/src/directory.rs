@@ -0,0 +1,552 @@
+//! instances.social client, directory filters and the listing cache.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::Path,
+    sync::RwLock,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use reqwest::{
+    blocking::Client as HttpClient,
+    header::{HeaderMap, AUTHORIZATION, RETRY_AFTER},
+    StatusCode,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::activity;
+use crate::advisories::AdvisoryList;
+use crate::error::Error;
+use crate::model::{FetchParams, JsInstance};
+use crate::nodeinfo;
+use crate::notices;
+use crate::privacy;
+use crate::probe::{self, ProbeClient};
+use crate::security::{self, Grade};
+use crate::USER_AGENT;
+
+const DEFAULT_API_BASE_URL: &str = "https://instances.social/api/1.0";
+/// Points the client at another directory server, e.g. `xtask mock-api`.
+pub const API_URL_ENV: &str = "INSTANCES_FINDER_API_URL";
+const HTTP_TIMEOUT_SECS: u64 = 20;
+// Longest Retry-After worth waiting for before giving up
+const MAX_RETRY_AFTER_SECS: u64 = 10;
+/// Servers probed at the same time.
+pub const PROBE_WORKERS: usize = 8;
+const CACHE_MAX_AGE_SECS: u64 = 24 * 3600;
+
+static API_BASE_URL: RwLock<Option<String>> = RwLock::new(None);
+
+/// Send directory requests to `url` (a mock server in tests) instead of
+/// instances.social; `None` goes back to the default.
+pub fn set_api_base_url(url: Option<&str>) {
+    *API_BASE_URL.write().unwrap() = url.map(|u| u.trim_end_matches('/').to_string());
+}
+
+/// Directory API root in use: the runtime override, then
+/// `INSTANCES_FINDER_API_URL`, then instances.social.
+pub fn api_base_url() -> String {
+    if let Some(url) = API_BASE_URL.read().unwrap().clone() {
+        return url;
+    }
+    std::env::var(API_URL_ENV)
+        .ok()
+        .map(|u| u.trim().trim_end_matches('/').to_string())
+        .filter(|u| !u.is_empty())
+        .unwrap_or_else(|| DEFAULT_API_BASE_URL.to_string())
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    saved_at: u64,
+    params: FetchParams,
+    items: Vec<JsInstance>,
+}
+
+struct InstancesSocialClient {
+    http: HttpClient,
+    token: String,
+}
+
+impl InstancesSocialClient {
+    fn new(token: &str) -> Result<Self, Error> {
+        let http = HttpClient::builder()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
+            .build()?;
+        Ok(Self {
+            http,
+            token: token.to_string(),
+        })
+    }
+
+    fn sample(&self, count: u64) -> Result<(), Error> {
+        let mut query = Vec::new();
+        if count > 0 {
+            query.push(("count", count.to_string()));
+        }
+        self.get::<serde_json::Value>("instances/sample", &query)
+            .map(|_| ())
+    }
+
+    /// Up to `count` instances, following `pagination.next_id` when the
+    /// directory returns them in several pages. The directory filters on
+    /// one `language` at a time.
+    fn list(
+        &self,
+        params: &FetchParams,
+        language: Option<&str>,
+        count: u64,
+    ) -> Result<Vec<Instance>, Error> {
+        let mut query = Vec::new();
+        if let Some(include_down) = params.include_down {
+            query.push(("include_down", include_down.to_string()));
+        }
+        if let Some(include_closed) = params.include_closed {
+            query.push(("include_closed", include_closed.to_string()));
+        }
+        if let Some(language) = language {
+            query.push(("language", language.to_string()));
+        }
+        let mut instances: Vec<Instance> = Vec::new();
+        let mut min_id: Option<String> = None;
+        while (instances.len() as u64) < count {
+            let mut page_query = query.clone();
+            page_query.push(("count", (count - instances.len() as u64).to_string()));
+            if let Some(id) = &min_id {
+                page_query.push(("min_id", id.clone()));
+            }
+            let page: ListResponse = self.get("instances/list", &page_query)?;
+            let next_id = page.pagination.and_then(|p| p.next_id);
+            let empty = page.instances.is_empty();
+            instances.extend(page.instances);
+            match next_id {
+                Some(id) if !empty => min_id = Some(id),
+                _ => break,
+            }
+        }
+        instances.truncate(usize::try_from(count).unwrap_or(usize::MAX));
+        Ok(instances)
+    }
+
+    fn get<T>(&self, path: &str, query: &[(&str, String)]) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let url = format!("{}/{path}", api_base_url());
+        let mut retried = false;
+        loop {
+            let mut request = self.http.get(&url);
+            if !query.is_empty() {
+                request = request.query(&query);
+            }
+            let response = request
+                .header(AUTHORIZATION, format!("Bearer {}", self.token))
+                .send()?;
+
+            let status = response.status();
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                // Wait once when the directory says how long; fail otherwise
+                match retry_after(response.headers()) {
+                    Some(secs) if !retried && secs <= MAX_RETRY_AFTER_SECS => {
+                        retried = true;
+                        thread::sleep(Duration::from_secs(secs));
+                        continue;
+                    }
+                    Some(secs) => return Err(Error::RateLimitedRetry { secs }),
+                    None => return Err(Error::RateLimited),
+                }
+            }
+            if !status.is_success() {
+                let body = response.text().unwrap_or_default();
+                return Err(Error::DirectoryStatus {
+                    status: status.to_string(),
+                    body,
+                });
+            }
+            return Ok(response.json()?);
+        }
+    }
+}
+
+/// Delay in seconds from a `Retry-After` header (HTTP dates are ignored).
+fn retry_after(headers: &HeaderMap) -> Option<u64> {
+    headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()
+}
+
+#[derive(Debug, Deserialize)]
+struct ListResponse {
+    instances: Vec<Instance>,
+    #[serde(default)]
+    pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pagination {
+    next_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Instance {
+    name: String,
+    up: bool,
+    users: String,
+    open_registrations: bool,
+    #[serde(default)]
+    version: Option<String>,
+    info: Option<InstanceInfo>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct InstanceInfo {
+    short_description: Option<String>,
+    languages: Option<Vec<String>>,
+}
+
+/// Rough region from the top-level domain: "eu", "na" or "other".
+pub fn map_region_from_domain(domain: &str) -> String {
+    let d = domain.to_lowercase();
+    let eu_tlds = [
+        ".eu", ".fr", ".de", ".it", ".es", ".pt", ".pl", ".nl", ".be", ".lu", ".ie", ".se", ".fi",
+        ".dk", ".cz", ".sk", ".si", ".hr", ".gr", ".bg", ".ro", ".hu", ".lt", ".lv", ".ee", ".cy",
+        ".mt", ".is", ".no",
+    ];
+    if d.ends_with(".us") || d.ends_with(".ca") {
+        return "na".into();
+    }
+    if eu_tlds.iter().any(|t| d.ends_with(t)) {
+        return "eu".into();
+    }
+    "other".into()
+}
+
+/// Size bucket used by the wizard: 1 small, 2 medium, 3 large.
+pub fn size_from_users(users: i64) -> u8 {
+    if users <= 2000 {
+        1
+    } else if users <= 10000 {
+        2
+    } else {
+        3
+    }
+}
+
+/// English name of a size class; apps relabel it in their own language.
+pub fn size_label(size: u8) -> &'static str {
+    if size == 1 {
+        "Small"
+    } else if size == 2 {
+        "Medium"
+    } else {
+        "Large"
+    }
+}
+
+/// What the listing cache holds, for `cache stats`.
+#[derive(Debug, Serialize)]
+pub struct CacheInfo {
+    pub saved_at: u64,
+    pub age_secs: u64,
+    pub items: usize,
+    pub params: FetchParams,
+}
+
+/// Cached listing for `params` if it is less than a day old.
+pub fn read_cache(path: &Path, params: &FetchParams) -> Option<Vec<JsInstance>> {
+    let cache = serde_json::from_slice::<CacheFile>(&fs::read(path).ok()?).ok()?;
+    let age = SystemTime::UNIX_EPOCH + Duration::from_secs(cache.saved_at);
+    (age.elapsed().unwrap_or_default() < Duration::from_secs(CACHE_MAX_AGE_SECS)
+        && &cache.params == params)
+        .then_some(cache.items)
+}
+
+pub fn write_cache(path: &Path, params: &FetchParams, items: &[JsInstance]) {
+    let cache = CacheFile {
+        saved_at: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+        params: params.clone(),
+        items: items.to_vec(),
+    };
+    if let Ok(bytes) = serde_json::to_vec(&cache) {
+        let _ = fs::write(path, bytes);
+    }
+}
+
+pub fn cache_info(path: &Path) -> Option<CacheInfo> {
+    let cache = serde_json::from_slice::<CacheFile>(&fs::read(path).ok()?).ok()?;
+    let age = SystemTime::UNIX_EPOCH + Duration::from_secs(cache.saved_at);
+    Some(CacheInfo {
+        saved_at: cache.saved_at,
+        age_secs: age.elapsed().unwrap_or_default().as_secs(),
+        items: cache.items.len(),
+        params: cache.params,
+    })
+}
+
+pub fn clear_cache(path: &Path) -> Result<(), Error> {
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+// One code or several separated by commas, as in links
+fn wanted_languages(params: &FetchParams) -> Vec<&str> {
+    params
+        .language
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+/// Check a token with the smallest possible request.
+pub fn test_token(token: &str) -> Result<(), Error> {
+    InstancesSocialClient::new(token)?.sample(1)
+}
+
+/// List instances.social matches for `params`, then probe servers for the
+/// filters and details the directory does not provide.
+pub fn search(
+    token: &str,
+    params: &FetchParams,
+    advisories: &AdvisoryList,
+) -> Result<Vec<JsInstance>, Error> {
+    let min_grade = params
+        .min_security
+        .as_deref()
+        .filter(|min| !min.trim().is_empty())
+        .map(str::parse::<Grade>)
+        .transpose()?;
+    let client = InstancesSocialClient::new(token)?;
+    let max = params.max.unwrap_or(200);
+    let languages = wanted_languages(params);
+    let queries: Vec<Option<&str>> = if languages.is_empty() {
+        vec![None]
+    } else {
+        languages.iter().copied().map(Some).collect()
+    };
+    let mut instances: Vec<Instance> = Vec::new();
+    for language in queries {
+        for instance in client.list(params, language, max as u64)? {
+            if !instances.iter().any(|i| i.name == instance.name) {
+                instances.push(instance);
+            }
+        }
+    }
+
+    let items: Vec<JsInstance> = instances
+        .into_iter()
+        .filter_map(|i| {
+            // Names the probe rules reject (underscores, odd spellings) are
+            // still listed as the directory gives them
+            let domain =
+                probe::normalize_domain(&i.name).unwrap_or_else(|_| i.name.trim().to_lowercase());
+            let users = i.users.parse::<u64>().ok();
+            let size = size_from_users(users.map_or(0, |u| i64::try_from(u).unwrap_or(i64::MAX)));
+            let langs = i
+                .info
+                .as_ref()
+                .and_then(|inf| inf.languages.clone())
+                .unwrap_or_default();
+            let region = map_region_from_domain(&domain);
+            // instances.social only lists Mastodon servers
+            let vulnerability = i
+                .version
+                .as_deref()
+                .and_then(|v| advisories.check("mastodon", v));
+
+            // Client-side filters
+            if let Some(sig) = &params.signups {
+                if (sig == "open" && !i.open_registrations)
+                    || (sig == "approval" && i.open_registrations)
+                {
+                    return None;
+                }
+            }
+            if let Some(reg) = &params.region {
+                if &region != reg {
+                    return None;
+                }
+            }
+            if let Some(sz) = &params.size {
+                let want = if sz == "small" {
+                    1
+                } else if sz == "medium" {
+                    2
+                } else {
+                    3
+                };
+                // keep near target (allow +-1 only for medium)
+                if sz == "medium" {
+                    if !(size == 1 || size == 2) {
+                        return None;
+                    }
+                } else if size != want {
+                    return None;
+                }
+            }
+            if !languages.is_empty() && !langs.iter().any(|l| languages.contains(&l.as_str())) {
+                return None;
+            }
+            if params.hide_vulnerable.unwrap_or(false)
+                && vulnerability
+                    .as_ref()
+                    .is_some_and(|v| v.severity == "critical")
+            {
+                return None;
+            }
+
+            Some(JsInstance {
+                domain,
+                description: i
+                    .info
+                    .as_ref()
+                    .and_then(|inf| inf.short_description.clone())
+                    .unwrap_or_default(),
+                languages: langs,
+                signups: if i.open_registrations {
+                    "open".into()
+                } else {
+                    "approval".into()
+                },
+                size,
+                size_label: size_label(size).into(),
+                region,
+                availability: if i.up { 0.999 } else { 0.4 },
+                users,
+                active_month: None,
+                // Only known for evaluated domains: probing every listed server
+                // would contact them on each search. Scoring falls back to
+                // the sign-up mode.
+                moderation: None,
+                accessibility: None,
+                software: None,
+                security: None,
+                privacy: None,
+                vulnerability,
+                notice: None,
+                activity: None,
+                probed: false,
+            })
+        })
+        .collect();
+    let items = match params.software.as_deref().map(str::trim) {
+        Some(software) if !software.is_empty() => {
+            let wanted = software.to_lowercase();
+            probe_filter(items, |client, item| {
+                match nodeinfo::fetch_software(client) {
+                    Ok(info) if info.name == wanted => {
+                        item.software = Some(info);
+                        true
+                    }
+                    _ => false,
+                }
+            })
+        }
+        _ => items,
+    };
+    let items = match min_grade {
+        Some(min) => probe_filter(items, |client, item| {
+            let Ok(summary) = security::audit(client) else {
+                return false;
+            };
+            let keep = summary.grade.meets(min);
+            item.security = Some(summary);
+            keep
+        }),
+        None => items,
+    };
+    let exclude_cdn = params.exclude_cdn.unwrap_or(false);
+    let exclude_third_party = params.exclude_third_party.unwrap_or(false);
+    let items = if exclude_cdn || exclude_third_party {
+        probe_filter(items, |client, item| {
+            let Ok(summary) = privacy::privacy_summary(client) else {
+                return false;
+            };
+            let rejected = (exclude_cdn && summary.fronted_by_cdn())
+                || (exclude_third_party && summary.has_third_party_requests());
+            item.privacy = Some(summary);
+            !rejected
+        })
+    } else {
+        items
+    };
+    // Enrichment only: flagged or quiet instances stay listed, ranked lower
+    let check_notices = params.check_notices.unwrap_or(false);
+    let check_activity = params.check_activity.unwrap_or(false);
+    let items = if check_notices || check_activity {
+        probe_each(items, |client, item| {
+            if check_notices {
+                item.notice = notices::detect_notice(client).ok().flatten();
+            }
+            if check_activity {
+                item.activity = activity::activity_report(client, item.users).ok();
+            }
+        })
+    } else {
+        items
+    };
+
+    Ok(items)
+}
+
+/// Filters that need data the directory lacks: probe each remaining
+/// candidate in parallel and keep those for which `keep` returns true
+/// (`keep` may also attach what it learned to the item). Hosts that
+/// cannot be probed at all are dropped.
+fn probe_filter<F>(items: Vec<JsInstance>, keep: F) -> Vec<JsInstance>
+where
+    F: Fn(&ProbeClient, &mut JsInstance) -> bool + Sync,
+{
+    probe::parallel_map(&items, PROBE_WORKERS, |item| {
+        let client = ProbeClient::new(&item.domain).ok()?;
+        let mut item = item.clone();
+        keep(&client, &mut item).then_some(item)
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Probe each item in parallel to add what `enrich` learns; items whose
+/// host cannot be probed are kept as they are.
+fn probe_each<F>(items: Vec<JsInstance>, enrich: F) -> Vec<JsInstance>
+where
+    F: Fn(&ProbeClient, &mut JsInstance) + Sync,
+{
+    probe::parallel_map(&items, PROBE_WORKERS, |item| {
+        let mut item = item.clone();
+        if let Ok(client) = ProbeClient::new(&item.domain) {
+            enrich(&client, &mut item);
+        }
+        item
+    })
+}
+
+/// Languages seen in a large sample of the directory, lowercased.
+pub fn languages(token: &str) -> Result<Vec<String>, Error> {
+    let client = InstancesSocialClient::new(token)?;
+    // Fetch a larger sample to discover languages
+    let instances = client.list(&FetchParams::default(), None, 500)?;
+    let mut set = BTreeSet::new();
+    for i in instances {
+        if let Some(info) = i.info {
+            if let Some(langs) = info.languages {
+                for l in langs {
+                    if !l.trim().is_empty() {
+                        set.insert(l.to_lowercase());
+                    }
+                }
+            }
+        }
+    }
+    Ok(set.into_iter().collect())
+}
@@ -0,0 +1,281 @@
+//! Errors returned by the core crate.
+//!
+//! Each kind has a stable [`Error::code`] and the values of its message
+//! ([`Error::params`]), so callers can show it in the user's language; the
+//! `Display` text is the English message.
+
+use std::{io, sync::PoisonError};
+
+use thiserror::Error;
+
+use crate::link::SCHEME;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum Error {
+    #[error("instances.social rate limit reached, retry in {secs} s")]
+    RateLimitedRetry { secs: u64 },
+    #[error("instances.social rate limit reached")]
+    RateLimited,
+    #[error("instances.social returned {status}: {body}")]
+    DirectoryStatus { status: String, body: String },
+    #[error("{url} returned {status}")]
+    HttpStatus { url: String, status: String },
+    #[error("{domain} timed out")]
+    TimedOut { domain: String },
+    #[error("could not connect to {domain}")]
+    Connect { domain: String },
+    #[error("{domain} sent an unreadable response")]
+    BadResponse { domain: String },
+    #[error("request to {domain} failed")]
+    Network { domain: String },
+    #[error("{domain} advertised a non-HTTPS URL: {url}")]
+    NotHttps { domain: String, url: String },
+    #[error("invalid domain: {domain}")]
+    InvalidDomain { domain: String },
+    #[error("{domain} does not resolve")]
+    NoResolve { domain: String },
+    #[error("the certificate of {domain} has expired")]
+    CertificateExpired { domain: String },
+    #[error("the certificate of {domain} is not valid yet")]
+    CertificateNotValidYet { domain: String },
+    #[error("the certificate of {domain} is not issued by a trusted authority")]
+    CertificateUntrusted { domain: String },
+    #[error("the certificate is not valid for {domain}")]
+    CertificateWrongName { domain: String },
+    #[error("the certificate of {domain} has been revoked")]
+    CertificateRevoked { domain: String },
+    #[error("the certificate of {domain} is invalid")]
+    CertificateInvalid { domain: String },
+    #[error("{domain} refused the secure connection")]
+    TlsHandshake { domain: String },
+    #[error("no Mastodon-compatible instance API")]
+    NoInstanceApi,
+    #[error("no NodeInfo discovery document")]
+    NoNodeinfo,
+    #[error("no supported NodeInfo schema (2.0/2.1)")]
+    NodeinfoSchema,
+    #[error("NodeInfo document not available")]
+    NodeinfoMissing,
+    #[error("unsupported file type: {path}")]
+    UnsupportedFile { path: String },
+    #[error("file too large: {path}")]
+    FileTooLarge { path: String },
+    #[error("file not found")]
+    FileNotFound,
+    #[error("permission denied")]
+    PermissionDenied,
+    #[error("file error: {error}")]
+    Io { error: String },
+    #[error("unreadable data: {error}")]
+    Data { error: String },
+    #[error("internal error: {error}")]
+    Internal { error: String },
+    #[error("invalid date: {date}")]
+    AdvisoryDate { date: String },
+    #[error("{id}: unknown severity {severity}")]
+    AdvisorySeverity { id: String, severity: String },
+    #[error("{id}: invalid fixed versions")]
+    AdvisoryVersions { id: String },
+    #[error("not a favorite: {domain}")]
+    NotFavorite { domain: String },
+    #[error("{path} was saved by a newer version of the app (format {version}, supported up to {supported})")]
+    FileVersion {
+        path: String,
+        version: u32,
+        supported: u32,
+    },
+    #[error("system keyring unavailable: {error}")]
+    Keyring { error: String },
+    #[error("link too long")]
+    LinkTooLong,
+    #[error("invalid link: {error}")]
+    InvalidLink { error: String },
+    #[error("not an {SCHEME}:// link")]
+    NotOurLink,
+    #[error("unexpected parts in link")]
+    LinkParts,
+    #[error("unexpected query in instance link")]
+    LinkQuery,
+    #[error("unknown link; expected search or instance")]
+    UnknownLink,
+    #[error("repeated parameter: {name}")]
+    RepeatedParameter { name: String },
+    #[error("invalid value for {name}: {value}")]
+    InvalidValue { name: String, value: String },
+    #[error("unknown parameter: {name}")]
+    UnknownParameter { name: String },
+}
+
+impl Error {
+    /// Key of the message in the `errors` section of the UI catalogs.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::RateLimitedRetry { .. } => "rate_limited_retry",
+            Error::RateLimited => "rate_limited",
+            Error::DirectoryStatus { .. } => "directory_status",
+            Error::HttpStatus { .. } => "http_status",
+            Error::TimedOut { .. } => "timed_out",
+            Error::Connect { .. } => "connect",
+            Error::BadResponse { .. } => "bad_response",
+            Error::Network { .. } => "network",
+            Error::NotHttps { .. } => "not_https",
+            Error::InvalidDomain { .. } => "invalid_domain",
+            Error::NoResolve { .. } => "no_resolve",
+            Error::CertificateExpired { .. } => "certificate_expired",
+            Error::CertificateNotValidYet { .. } => "certificate_not_valid_yet",
+            Error::CertificateUntrusted { .. } => "certificate_untrusted",
+            Error::CertificateWrongName { .. } => "certificate_wrong_name",
+            Error::CertificateRevoked { .. } => "certificate_revoked",
+            Error::CertificateInvalid { .. } => "certificate_invalid",
+            Error::TlsHandshake { .. } => "tls_handshake",
+            Error::NoInstanceApi => "no_instance_api",
+            Error::NoNodeinfo => "no_nodeinfo",
+            Error::NodeinfoSchema => "nodeinfo_schema",
+            Error::NodeinfoMissing => "nodeinfo_missing",
+            Error::UnsupportedFile { .. } => "unsupported_file",
+            Error::FileTooLarge { .. } => "file_too_large",
+            Error::FileNotFound => "file_not_found",
+            Error::PermissionDenied => "permission_denied",
+            Error::Io { .. } => "io",
+            Error::Data { .. } => "data",
+            Error::Internal { .. } => "internal",
+            Error::AdvisoryDate { .. } => "advisory_date",
+            Error::AdvisorySeverity { .. } => "advisory_severity",
+            Error::AdvisoryVersions { .. } => "advisory_versions",
+            Error::NotFavorite { .. } => "not_favorite",
+            Error::FileVersion { .. } => "file_version",
+            Error::Keyring { .. } => "keyring",
+            Error::LinkTooLong => "link_too_long",
+            Error::InvalidLink { .. } => "invalid_link",
+            Error::NotOurLink => "not_our_link",
+            Error::LinkParts => "link_parts",
+            Error::LinkQuery => "link_query",
+            Error::UnknownLink => "unknown_link",
+            Error::RepeatedParameter { .. } => "repeated_parameter",
+            Error::InvalidValue { .. } => "invalid_value",
+            Error::UnknownParameter { .. } => "unknown_parameter",
+        }
+    }
+
+    /// Values of the `{name}` placeholders of the message.
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Error::RateLimitedRetry { secs } => vec![("secs", secs.to_string())],
+            Error::DirectoryStatus { status, body } => {
+                vec![("status", status.clone()), ("body", body.clone())]
+            }
+            Error::HttpStatus { url, status } => {
+                vec![("url", url.clone()), ("status", status.clone())]
+            }
+            Error::NotHttps { domain, url } => {
+                vec![("domain", domain.clone()), ("url", url.clone())]
+            }
+            Error::TimedOut { domain }
+            | Error::Connect { domain }
+            | Error::BadResponse { domain }
+            | Error::Network { domain }
+            | Error::InvalidDomain { domain }
+            | Error::NoResolve { domain }
+            | Error::CertificateExpired { domain }
+            | Error::CertificateNotValidYet { domain }
+            | Error::CertificateUntrusted { domain }
+            | Error::CertificateWrongName { domain }
+            | Error::CertificateRevoked { domain }
+            | Error::CertificateInvalid { domain }
+            | Error::TlsHandshake { domain }
+            | Error::NotFavorite { domain } => vec![("domain", domain.clone())],
+            Error::UnsupportedFile { path } | Error::FileTooLarge { path } => {
+                vec![("path", path.clone())]
+            }
+            Error::Io { error }
+            | Error::Data { error }
+            | Error::Internal { error }
+            | Error::Keyring { error }
+            | Error::InvalidLink { error } => vec![("error", error.clone())],
+            Error::AdvisoryDate { date } => vec![("date", date.clone())],
+            Error::AdvisorySeverity { id, severity } => {
+                vec![("id", id.clone()), ("severity", severity.clone())]
+            }
+            Error::AdvisoryVersions { id } => vec![("id", id.clone())],
+            Error::FileVersion {
+                path,
+                version,
+                supported,
+            } => vec![
+                ("path", path.clone()),
+                ("version", version.to_string()),
+                ("supported", supported.to_string()),
+            ],
+            Error::RepeatedParameter { name } | Error::UnknownParameter { name } => {
+                vec![("name", name.clone())]
+            }
+            Error::InvalidValue { name, value } => {
+                vec![("name", name.clone()), ("value", value.clone())]
+            }
+            Error::RateLimited
+            | Error::NoInstanceApi
+            | Error::NoNodeinfo
+            | Error::NodeinfoSchema
+            | Error::NodeinfoMissing
+            | Error::FileNotFound
+            | Error::PermissionDenied
+            | Error::LinkTooLong
+            | Error::NotOurLink
+            | Error::LinkParts
+            | Error::LinkQuery
+            | Error::UnknownLink => Vec::new(),
+        }
+    }
+}
+
+// Sorted by what the user can act on; reqwest's own text names internals
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        let domain = e
+            .url()
+            .and_then(|u| u.host_str())
+            .unwrap_or_default()
+            .to_string();
+        if e.is_timeout() {
+            Error::TimedOut { domain }
+        } else if e.is_connect() {
+            Error::Connect { domain }
+        } else if e.is_decode() || e.is_body() {
+            Error::BadResponse { domain }
+        } else if e.is_builder() {
+            Error::Internal {
+                error: e.to_string(),
+            }
+        } else {
+            Error::Network { domain }
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::NotFound => Error::FileNotFound,
+            io::ErrorKind::PermissionDenied => Error::PermissionDenied,
+            _ => Error::Io {
+                error: e.to_string(),
+            },
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Data {
+            error: e.to_string(),
+        }
+    }
+}
+
+impl<T> From<PoisonError<T>> for Error {
+    fn from(e: PoisonError<T>) -> Self {
+        Error::Internal {
+            error: e.to_string(),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Build a result entry for a server that is not in the directory.
+
+use serde::Deserialize;
+
+use crate::accessibility;
+use crate::activity;
+use crate::advisories::AdvisoryList;
+use crate::directory::{map_region_from_domain, size_from_users, size_label};
+use crate::error::Error;
+use crate::model::JsInstance;
+use crate::moderation;
+use crate::nodeinfo;
+use crate::notices;
+use crate::privacy;
+use crate::probe::ProbeClient;
+use crate::security;
+
+#[derive(Debug, Deserialize)]
+struct InstanceV2 {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    languages: Vec<String>,
+    registrations: RegistrationsV2,
+    usage: Option<UsageV2>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegistrationsV2 {
+    enabled: bool,
+    #[serde(default)]
+    approval_required: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageV2 {
+    users: UsageUsersV2,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageUsersV2 {
+    active_month: u64,
+}
+
+// Mastodon < 4.0 and most other software only expose the v1 endpoint
+#[derive(Debug, Deserialize)]
+struct InstanceV1 {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    short_description: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    languages: Vec<String>,
+    #[serde(default)]
+    registrations: bool,
+    #[serde(default)]
+    approval_required: bool,
+    stats: Option<StatsV1>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatsV1 {
+    user_count: u64,
+}
+
+struct InstanceFacts {
+    version: String,
+    description: String,
+    languages: Vec<String>,
+    signups: &'static str,
+    users: Option<u64>,
+    active_month: Option<u64>,
+}
+
+fn instance_facts(client: &ProbeClient) -> Result<InstanceFacts, Error> {
+    if let Some(v2) = client.get_public::<InstanceV2>("/api/v2/instance", &[])? {
+        return Ok(InstanceFacts {
+            version: v2.version,
+            description: v2.description,
+            languages: v2.languages,
+            signups: signups(v2.registrations.enabled, v2.registrations.approval_required),
+            // v2 only counts monthly active accounts
+            users: None,
+            active_month: v2.usage.map(|u| u.users.active_month),
+        });
+    }
+    let v1: InstanceV1 = client
+        .get_public("/api/v1/instance", &[])?
+        .ok_or(Error::NoInstanceApi)?;
+    Ok(InstanceFacts {
+        version: v1.version,
+        description: if v1.short_description.trim().is_empty() {
+            v1.description
+        } else {
+            v1.short_description
+        },
+        languages: v1.languages,
+        signups: signups(v1.registrations, v1.approval_required),
+        users: v1.stats.map(|s| s.user_count),
+        active_month: None,
+    })
+}
+
+fn signups(enabled: bool, approval_required: bool) -> &'static str {
+    if !enabled {
+        "closed"
+    } else if approval_required {
+        "approval"
+    } else {
+        "open"
+    }
+}
+
+/// Current registration mode: "open", "approval" or "closed".
+pub fn registration_status(client: &ProbeClient) -> Result<&'static str, Error> {
+    instance_facts(client).map(|facts| facts.signups)
+}
+
+/// Probe a domain directly and describe it like a directory entry.
+///
+/// The instance API is required; NodeInfo, moderation, accessibility,
+/// transport security, privacy, activity data and service notices are
+/// added when available.
+pub fn evaluate(client: &ProbeClient, advisories: &AdvisoryList) -> Result<JsInstance, Error> {
+    let domain = client.domain();
+    let facts = instance_facts(client)?;
+    let software = nodeinfo::fetch_software(client).ok();
+
+    // NodeInfo reports total accounts like instances.social does; the v2
+    // instance API only has monthly active users, still a lower bound for
+    // the size.
+    let users = software.as_ref().and_then(|s| s.users).or(facts.users);
+    let active_month = software
+        .as_ref()
+        .and_then(|s| s.active_month)
+        .or(facts.active_month);
+    let size = size_from_users(
+        users
+            .or(active_month)
+            .map_or(0, |u| i64::try_from(u).unwrap_or(i64::MAX)),
+    );
+    // Other servers answer the instance API with a version like
+    // "2.7.2 (compatible; Pleroma 2.5.0)", which means nothing here
+    let vulnerability = match &software {
+        Some(s) => advisories.check(&s.name, &s.version),
+        None if !facts.version.contains("compatible") => {
+            advisories.check("mastodon", &facts.version)
+        }
+        None => None,
+    };
+
+    Ok(JsInstance {
+        domain: domain.to_string(),
+        description: facts.description.trim().to_string(),
+        languages: facts.languages.iter().map(|l| l.to_lowercase()).collect(),
+        signups: facts.signups.into(),
+        size,
+        size_label: size_label(size).into(),
+        region: map_region_from_domain(domain),
+        // The instance answered over HTTPS just now
+        availability: 0.999,
+        users,
+        active_month,
+        moderation: moderation::moderation_report(client).ok(),
+        accessibility: accessibility::accessibility_report(client).ok(),
+        software,
+        security: security::audit(client).ok(),
+        privacy: privacy::privacy_summary(client).ok(),
+        vulnerability,
+        notice: notices::detect_notice(client).ok().flatten(),
+        activity: activity::activity_report(client, users).ok(),
+        probed: true,
+    })
+}
@@ -0,0 +1,213 @@
+//! Shortlist of instances saved with notes, and what changed since.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::model::JsInstance;
+use crate::probe;
+use crate::store::{self, now};
+
+const FORMAT_VERSION: u32 = 1;
+
+/// A starred instance with the data it had when it was starred.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Favorite {
+    pub domain: String,
+    #[serde(default)]
+    pub note: String,
+    pub added_at: u64,
+    pub snapshot: JsInstance,
+}
+
+/// One field that differs between the snapshot and the live instance.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FavoriteView {
+    #[serde(flatten)]
+    pub favorite: Favorite,
+    /// Live data, when the favorite was refreshed and the host answered.
+    pub current: Option<JsInstance>,
+    pub changes: Vec<FieldChange>,
+}
+
+/// The user's shortlist, in the order they chose.
+pub struct FavoritesStore {
+    path: PathBuf,
+}
+
+impl FavoritesStore {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    pub fn list(&self) -> Result<Vec<Favorite>, Error> {
+        let _guard = store::lock()?;
+        self.load()
+    }
+
+    /// Star an instance, or refresh the snapshot of an existing favorite
+    /// (keeping its note and position).
+    pub fn add(&self, instance: JsInstance, note: Option<String>) -> Result<Vec<Favorite>, Error> {
+        let domain = probe::normalize_domain(&instance.domain)?;
+        self.update(|items| {
+            match items.iter_mut().find(|f| f.domain == domain) {
+                Some(existing) => {
+                    existing.snapshot = instance;
+                    if let Some(note) = note {
+                        existing.note = note;
+                    }
+                }
+                None => items.push(Favorite {
+                    domain,
+                    note: note.unwrap_or_default(),
+                    added_at: now(),
+                    snapshot: instance,
+                }),
+            }
+            Ok(())
+        })
+    }
+
+    pub fn remove(&self, domain: &str) -> Result<Vec<Favorite>, Error> {
+        let domain = probe::normalize_domain(domain)?;
+        self.update(|items| {
+            items.retain(|f| f.domain != domain);
+            Ok(())
+        })
+    }
+
+    pub fn set_note(&self, domain: &str, note: &str) -> Result<Vec<Favorite>, Error> {
+        let domain = probe::normalize_domain(domain)?;
+        self.update(|items| {
+            let favorite = items
+                .iter_mut()
+                .find(|f| f.domain == domain)
+                .ok_or_else(|| Error::NotFavorite {
+                    domain: domain.clone(),
+                })?;
+            favorite.note = note.trim().to_string();
+            Ok(())
+        })
+    }
+
+    /// Put the listed domains first, in that order; others keep their
+    /// relative order after them.
+    pub fn reorder(&self, domains: &[String]) -> Result<Vec<Favorite>, Error> {
+        self.update(|items| {
+            let position = |f: &Favorite| {
+                domains
+                    .iter()
+                    .position(|d| *d == f.domain)
+                    .unwrap_or(usize::MAX)
+            };
+            items.sort_by_key(position);
+            Ok(())
+        })
+    }
+
+    fn update<F>(&self, change: F) -> Result<Vec<Favorite>, Error>
+    where
+        F: FnOnce(&mut Vec<Favorite>) -> Result<(), Error>,
+    {
+        let _guard = store::lock()?;
+        let mut items = self.load()?;
+        change(&mut items)?;
+        self.save(&items)?;
+        Ok(items)
+    }
+
+    fn load(&self) -> Result<Vec<Favorite>, Error> {
+        store::read(&self.path, FORMAT_VERSION)
+    }
+
+    fn save(&self, items: &[Favorite]) -> Result<(), Error> {
+        store::write(&self.path, FORMAT_VERSION, &items)
+    }
+}
+
+type FieldValue = fn(&JsInstance) -> String;
+
+/// When a field can be compared between a snapshot and live data.
+#[derive(Clone, Copy, PartialEq)]
+enum Compare {
+    Always,
+    /// Probe-only data, missing from directory snapshots: compared only
+    /// when both sides have a value.
+    BothKnown,
+    /// The directory and the instance itself may disagree: compared only
+    /// when both sides come from the same source.
+    SameSource,
+}
+
+/// Fields a user would care about when comparing a favorite with its
+/// current state. Availability is left out: directory and direct probes
+/// estimate it differently.
+pub fn diff(before: &JsInstance, after: &JsInstance) -> Vec<FieldChange> {
+    let fields: [(&str, FieldValue, Compare); 9] = [
+        (
+            "description",
+            |i| i.description.clone(),
+            Compare::SameSource,
+        ),
+        ("languages", |i| i.languages.join(", "), Compare::SameSource),
+        ("signups", |i| i.signups.clone(), Compare::SameSource),
+        ("sizeLabel", |i| i.size_label.clone(), Compare::Always),
+        (
+            "vulnerability",
+            |i| optional(&i.vulnerability, |v| v.severity.clone()),
+            Compare::Always,
+        ),
+        (
+            "notice",
+            |i| optional(&i.notice, |n| n.kind.clone()),
+            Compare::Always,
+        ),
+        (
+            "software",
+            |i| optional(&i.software, |s| format!("{} {}", s.name, s.version)),
+            Compare::BothKnown,
+        ),
+        (
+            "moderation",
+            |i| optional(&i.moderation, |m| m.style.clone()),
+            Compare::BothKnown,
+        ),
+        (
+            "security",
+            |i| optional(&i.security, |s| s.grade.to_string()),
+            Compare::BothKnown,
+        ),
+    ];
+    fields
+        .iter()
+        .filter_map(|(field, value, compare)| {
+            let (b, a) = (value(before), value(after));
+            let comparable = match compare {
+                Compare::Always => true,
+                Compare::BothKnown => !b.is_empty() && !a.is_empty(),
+                Compare::SameSource => before.probed == after.probed,
+            };
+            (comparable && b != a).then(|| FieldChange {
+                field: field.to_string(),
+                before: b,
+                after: a,
+            })
+        })
+        .collect()
+}
+
+fn optional<T>(value: &Option<T>, show: impl Fn(&T) -> String) -> String {
+    value.as_ref().map(show).unwrap_or_default()
+}
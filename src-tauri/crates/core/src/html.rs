@@ -0,0 +1,338 @@
+//! Just enough HTML handling for server pages: links, attributes, text.
+
+/// Convert the small HTML subset used by Mastodon (posts, descriptions,
+/// announcements) to plain text: paragraphs and line breaks are kept,
+/// other tags are dropped and entities decoded.
+pub fn to_plain_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&decode_entities(&rest[..start]));
+        let Some(end) = rest[start..].find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim().to_ascii_lowercase();
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        match name {
+            "br" => out.push('\n'),
+            "p" | "div" | "li" | "blockquote" if tag.starts_with('/') => out.push_str("\n\n"),
+            _ => {}
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(&decode_entities(rest));
+    collapse_blank_lines(&out)
+}
+
+/// Like [`to_plain_text`], with links spelled out for screen readers:
+/// mentions and hashtags keep their text, other links become
+/// `label [link: host]`, or `[link: host]` when the label is the URL;
+/// `link_word` is the word for "link" in the reader's language.
+pub fn to_readable_text(html: &str, link_word: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    // (href, class, label) of the link being read
+    let mut link: Option<(String, String, String)> = None;
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        let text = decode_entities(&rest[..start]);
+        match &mut link {
+            Some((_, _, label)) => label.push_str(&text),
+            None => out.push_str(&text),
+        }
+        let Some(end) = rest[start..].find('>') else {
+            rest = "";
+            break;
+        };
+        let raw = &rest[start + 1..start + end];
+        let tag = raw.trim().to_ascii_lowercase();
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        match name {
+            "a" if !closing => {
+                link = Some((
+                    attribute(raw, "href").unwrap_or_default(),
+                    attribute(raw, "class").unwrap_or_default(),
+                    String::new(),
+                ));
+            }
+            "a" => {
+                if let Some((href, class, label)) = link.take() {
+                    out.push_str(&name_link(&href, &class, label.trim(), link_word));
+                }
+            }
+            "br" => out.push('\n'),
+            "p" | "div" | "li" | "blockquote" if closing => out.push_str("\n\n"),
+            _ => {}
+        }
+        rest = &rest[start + end + 1..];
+    }
+    let tail = decode_entities(rest);
+    match link {
+        // Unclosed link: its text runs to the end
+        Some((href, class, mut label)) => {
+            label.push_str(&tail);
+            out.push_str(&name_link(&href, &class, label.trim(), link_word));
+        }
+        None => out.push_str(&tail),
+    }
+    collapse_blank_lines(&out)
+}
+
+fn name_link(href: &str, class: &str, label: &str, link_word: &str) -> String {
+    let is_social = class
+        .split_whitespace()
+        .any(|c| c == "mention" || c == "hashtag")
+        || label.starts_with('@')
+        || label.starts_with('#');
+    if is_social {
+        return label.to_string();
+    }
+    let host = href
+        .split_once("://")
+        .map_or(href, |(_, rest)| rest)
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches("www.");
+    if host.is_empty() {
+        return label.to_string();
+    }
+    let label_is_url = label.is_empty()
+        || label.starts_with("http")
+        || label.trim_start_matches("www.").starts_with(host);
+    if label_is_url {
+        format!("[{link_word}: {host}]")
+    } else {
+        format!("{label} [{link_word}: {host}]")
+    }
+}
+
+/// Decode the named entities Mastodon emits plus numeric references.
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let decoded = after.find(';').filter(|&i| i <= 10).and_then(|semi| {
+            let entity = &after[..semi];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, semi))
+        });
+        match decoded {
+            Some((c, semi)) => {
+                out.push(c);
+                rest = &after[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// URLs of the resources a page loads on its own: script and image
+/// sources, plus stylesheet, icon and preload links.
+pub fn resource_urls(html: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let url = match name.as_str() {
+            "script" | "img" => attribute(tag, "src"),
+            "link" => attribute(tag, "rel")
+                .filter(|rel| {
+                    rel.split_whitespace().any(|r| {
+                        matches!(
+                            r.to_ascii_lowercase().as_str(),
+                            "stylesheet" | "icon" | "preload" | "modulepreload"
+                        )
+                    })
+                })
+                .and_then(|_| attribute(tag, "href")),
+            _ => None,
+        };
+        if let Some(url) = url.filter(|u| !u.is_empty()) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+/// Value of a quoted or bare attribute inside a start tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find(name) {
+        let at = from + found;
+        from = at + name.len();
+        let preceded_by_space = lower[..at].ends_with(|c: char| c.is_whitespace());
+        let after = lower[from..].trim_start();
+        if !preceded_by_space || !after.starts_with('=') {
+            continue;
+        }
+        let value = tag[tag.len() - after.len() + 1..].trim_start();
+        let raw = match value.chars().next() {
+            Some(q @ ('"' | '\'')) => value[1..].split(q).next().unwrap_or_default(),
+            _ => value.split(char::is_whitespace).next().unwrap_or_default(),
+        };
+        return Some(decode_entities(raw.trim()));
+    }
+    None
+}
+
+fn collapse_blank_lines(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_run = 0;
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            blank_run += 1;
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if blank_run > 0 { "\n\n" } else { "\n" });
+        }
+        blank_run = 0;
+        out.push_str(line.trim_start());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readable_text_keeps_mentions_and_names_link_hosts() {
+        let html = concat!(
+            r#"<p>Hello <a href="https://mastodon.social/@alice" class="u-url mention">"#,
+            r#"@<span>alice</span></a> and <a href="https://x.example/tags/a11y" "#,
+            r#"class="mention hashtag" rel="tag">#<span>a11y</span></a></p>"#,
+            r#"<p>Read <a href="https://www.example.org/post">this article</a></p>"#,
+        );
+        assert_eq!(
+            to_readable_text(html, "link"),
+            "Hello @alice and #a11y\n\nRead this article [link: example.org]"
+        );
+    }
+
+    #[test]
+    fn readable_text_shortens_links_labelled_with_their_url() {
+        let html = concat!(
+            r#"<a href="https://example.org/a/b"><span class="invisible">https://</span>"#,
+            r#"<span>example.org/a/b</span></a>"#,
+        );
+        assert_eq!(to_readable_text(html, "lien"), "[lien: example.org]");
+        assert_eq!(
+            to_readable_text(r#"<a href="https://e.example/x"></a>"#, "link"),
+            "[link: e.example]"
+        );
+        // No host to name: the label alone
+        assert_eq!(
+            to_readable_text(r#"<a href="/about">About</a>"#, "link"),
+            "About"
+        );
+    }
+
+    #[test]
+    fn readable_text_handles_breaks_entities_and_unclosed_links() {
+        assert_eq!(to_readable_text("a<br>b<br />c", "link"), "a\nb\nc");
+        assert_eq!(
+            to_readable_text("Fish &amp; chips &#x1F41F; &bogus; &#65;", "link"),
+            "Fish & chips \u{1F41F} &bogus; A"
+        );
+        assert_eq!(
+            to_readable_text(r#"see <a href="https://e.example/doc">the docs"#, "link"),
+            "see the docs [link: e.example]"
+        );
+        assert_eq!(to_readable_text("cut <b", "link"), "cut");
+    }
+
+    #[test]
+    fn resource_urls_lists_loaded_resources_only() {
+        let html = concat!(
+            r#"<script src="/packs/app.js"></script>"#,
+            r#"<img src='https://cdn.example/a.png' alt="x"><img src="">"#,
+            r#"<link rel="stylesheet" href="https://fonts.example/css">"#,
+            r#"<link rel="alternate" href="https://x.example/feed">"#,
+            r#"<link rel="Preload" as="font" href=//fonts.example/f.woff2>"#,
+            r#"<a href="https://elsewhere.example/">x</a><IMG SRC="b.png">"#,
+        );
+        assert_eq!(
+            resource_urls(html),
+            [
+                "/packs/app.js",
+                "https://cdn.example/a.png",
+                "https://fonts.example/css",
+                "//fonts.example/f.woff2",
+                "b.png",
+            ]
+        );
+        assert!(resource_urls("no tags <here").is_empty());
+    }
+
+    #[test]
+    fn attribute_reads_quoted_and_bare_values() {
+        assert_eq!(
+            attribute(r#"a href="x" class='y'"#, "class").as_deref(),
+            Some("y")
+        );
+        assert_eq!(attribute("a HREF = 'X Y'", "href").as_deref(), Some("X Y"));
+        assert_eq!(
+            attribute("img src=pic.png alt=x", "src").as_deref(),
+            Some("pic.png")
+        );
+        assert_eq!(
+            attribute(r#"a title="Fish &amp; chips""#, "title").as_deref(),
+            Some("Fish & chips")
+        );
+    }
+
+    #[test]
+    fn attribute_skips_longer_names() {
+        assert_eq!(
+            attribute(r#"a data-href="y" href="x""#, "href").as_deref(),
+            Some("x")
+        );
+        assert_eq!(attribute(r#"a hrefx="1""#, "href"), None);
+        assert_eq!(attribute("a href", "href"), None);
+        assert_eq!(attribute("a", "href"), None);
+    }
+}
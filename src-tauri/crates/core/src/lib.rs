@@ -0,0 +1,55 @@
+//! Directory client, server probes, filters, ranking and local stores used
+//! by Instances Finder, without any Tauri or webview dependency.
+//!
+//! All network calls are blocking (`reqwest::blocking`); run them off any
+//! UI thread. Errors are [`error::Error`] values: an English message with a
+//! stable code and parameters, so callers can translate them.
+//!
+//! ```no_run
+//! use instances_finder_core::advisories::AdvisoryList;
+//! use instances_finder_core::model::FetchParams;
+//! use instances_finder_core::score::{self, Preferences};
+//! use instances_finder_core::directory;
+//!
+//! let params = FetchParams {
+//!     language: Some("fr".into()),
+//!     signups: Some("open".into()),
+//!     ..FetchParams::default()
+//! };
+//! let mut items = directory::search("token", &params, &AdvisoryList::bundled())?;
+//! let prefs = Preferences {
+//!     languages: vec!["fr".into()],
+//!     ..Preferences::default()
+//! };
+//! score::rank_instances(&mut items, &prefs);
+//! # Ok::<(), instances_finder_core::error::Error>(())
+//! ```
+
+pub mod accessibility;
+pub mod activity;
+pub mod advisories;
+pub mod batch;
+pub mod directory;
+pub mod error;
+pub mod evaluate;
+pub mod favorites;
+pub mod html;
+pub mod link;
+pub mod model;
+pub mod moderation;
+pub mod nodeinfo;
+pub mod notices;
+pub mod preview;
+pub mod privacy;
+pub mod probe;
+pub mod recent;
+pub mod score;
+pub mod security;
+pub mod storage;
+mod store;
+pub mod token;
+pub mod uptime;
+pub mod watchlist;
+
+/// Sent with every request, to instances.social and to probed servers.
+pub const USER_AGENT: &str = concat!("instances-finder/", env!("CARGO_PKG_VERSION"));
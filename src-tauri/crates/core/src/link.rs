@@ -0,0 +1,221 @@
+//! `instances-finder://` links: a search to run or an instance to open.
+//!
+//! - `instances-finder://search?lang=fr&size=small&signups=open`
+//! - `instances-finder://instance/mastodon.social`
+//!
+//! Links come from web pages and other apps, so parsing is strict: unknown
+//! or repeated parameters and unexpected values are errors, not ignored.
+
+use reqwest::Url;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::model::FetchParams;
+use crate::probe;
+use crate::score::Preferences;
+
+pub const SCHEME: &str = "instances-finder";
+
+const MAX_LINK_LEN: usize = 2048;
+const MAX_LANGUAGES: usize = 10;
+const SOFTWARE: &[&str] = &[
+    "mastodon",
+    "glitch-soc",
+    "hometown",
+    "pleroma",
+    "akkoma",
+    "gotosocial",
+    "misskey",
+];
+
+/// What a link asks for, as sent to the webview.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeepLink {
+    /// Wizard preferences to apply, and the directory filters they imply.
+    Search {
+        params: FetchParams,
+        preferences: Box<Preferences>,
+    },
+    /// Open the details of one server.
+    Instance { domain: String },
+}
+
+/// Parse and validate an `instances-finder://` link.
+pub fn parse(link: &str) -> Result<DeepLink, Error> {
+    if link.len() > MAX_LINK_LEN {
+        return Err(Error::LinkTooLong);
+    }
+    let url = Url::parse(link).map_err(|e| Error::InvalidLink {
+        error: e.to_string(),
+    })?;
+    if url.scheme() != SCHEME {
+        return Err(Error::NotOurLink);
+    }
+    if url.fragment().is_some() || !url.username().is_empty() || url.port().is_some() {
+        return Err(Error::LinkParts);
+    }
+    match url.host_str() {
+        Some("search") if matches!(url.path(), "" | "/") => {
+            let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+            search(&pairs)
+        }
+        Some("instance") => {
+            if url.query().is_some() {
+                return Err(Error::LinkQuery);
+            }
+            let domain = url.path().strip_prefix('/').unwrap_or_default();
+            Ok(DeepLink::Instance {
+                domain: strict_domain(domain)?,
+            })
+        }
+        _ => Err(Error::UnknownLink),
+    }
+}
+
+/// A search from `name=value` options, as in the query of a search link.
+pub fn search(pairs: &[(String, String)]) -> Result<DeepLink, Error> {
+    let preferences = search_preferences(pairs)?;
+    Ok(DeepLink::Search {
+        params: fetch_params(&preferences),
+        preferences: Box::new(preferences),
+    })
+}
+
+/// Preferences from `name=value` search options, the same names as in
+/// links (`lang`, `size`, `signups`…). Anything unknown is rejected.
+pub fn search_preferences(pairs: &[(String, String)]) -> Result<Preferences, Error> {
+    let mut prefs = Preferences {
+        size: "any".into(),
+        moderation: "any".into(),
+        signups: "any".into(),
+        region: "any".into(),
+        nsfw: "any".into(),
+        software: "any".into(),
+        security: "any".into(),
+        privacy: "any".into(),
+        vulnerable: "any".into(),
+        sort: "relevance".into(),
+        ..Preferences::default()
+    };
+    let mut seen: Vec<&str> = Vec::new();
+    for (name, value) in pairs {
+        if seen.contains(&name.as_str()) {
+            return Err(Error::RepeatedParameter { name: name.clone() });
+        }
+        seen.push(name.as_str());
+        let choice = |allowed: &[&str]| -> Result<String, Error> {
+            allowed
+                .iter()
+                .find(|a| **a == value)
+                .map(|a| a.to_string())
+                .ok_or_else(|| Error::InvalidValue {
+                    name: name.clone(),
+                    value: value.clone(),
+                })
+        };
+        match name.as_str() {
+            "lang" => prefs.languages = languages(value)?,
+            "size" => prefs.size = choice(&["any", "small", "medium", "large"])?,
+            "signups" => prefs.signups = choice(&["any", "open", "approval"])?,
+            "region" => prefs.region = choice(&["any", "eu", "na", "other"])?,
+            "moderation" => prefs.moderation = choice(&["any", "open", "balanced", "strict"])?,
+            "nsfw" => prefs.nsfw = choice(&["any", "allowed", "limited"])?,
+            "software" => {
+                prefs.software = if value == "any" {
+                    value.clone()
+                } else {
+                    choice(SOFTWARE)?
+                }
+            }
+            "security" => prefs.security = choice(&["any", "A", "B", "C"])?,
+            "privacy" => prefs.privacy = choice(&["any", "no_cdn", "no_third_party", "strict"])?,
+            "vulnerable" => prefs.vulnerable = choice(&["any", "hide"])?,
+            "sort" => prefs.sort = choice(&["relevance", "activity", "growth", "peers"])?,
+            _ => return Err(Error::UnknownParameter { name: name.clone() }),
+        }
+    }
+    Ok(prefs)
+}
+
+// "fr" or "fr,en": ISO 639 codes only
+fn languages(value: &str) -> Result<Vec<String>, Error> {
+    let codes: Vec<String> = value.split(',').map(str::to_string).collect();
+    let valid =
+        |c: &String| (2..=3).contains(&c.len()) && c.bytes().all(|b| b.is_ascii_lowercase());
+    if codes.len() > MAX_LANGUAGES || !codes.iter().all(valid) {
+        return Err(Error::InvalidValue {
+            name: "lang".into(),
+            value: value.to_string(),
+        });
+    }
+    Ok(codes)
+}
+
+/// Directory filters for `prefs`, as the webview builds them.
+pub fn fetch_params(prefs: &Preferences) -> FetchParams {
+    let chosen = |v: &str| (v != "any" && !v.is_empty()).then(|| v.to_string());
+    let privacy = prefs.privacy.as_str();
+    FetchParams {
+        language: (!prefs.languages.is_empty()).then(|| prefs.languages.join(",")),
+        signups: chosen(&prefs.signups),
+        region: chosen(&prefs.region),
+        size: chosen(&prefs.size),
+        software: chosen(&prefs.software),
+        min_security: chosen(&prefs.security),
+        exclude_cdn: Some(matches!(privacy, "no_cdn" | "strict")),
+        exclude_third_party: Some(matches!(privacy, "no_third_party" | "strict")),
+        hide_vulnerable: Some(prefs.vulnerable == "hide"),
+        ..FetchParams::default()
+    }
+}
+
+/// `name=value` search options matching the directory filters `params`,
+/// the reverse of [`fetch_params`]. Only the filters a link can carry are
+/// kept.
+pub fn search_query(params: &FetchParams) -> Vec<(String, String)> {
+    let privacy = match (params.exclude_cdn, params.exclude_third_party) {
+        (Some(true), Some(true)) => Some("strict"),
+        (Some(true), _) => Some("no_cdn"),
+        (_, Some(true)) => Some("no_third_party"),
+        _ => None,
+    };
+    [
+        ("lang", params.language.as_deref()),
+        ("size", params.size.as_deref()),
+        ("signups", params.signups.as_deref()),
+        ("region", params.region.as_deref()),
+        ("software", params.software.as_deref()),
+        ("security", params.min_security.as_deref()),
+        ("privacy", privacy),
+        (
+            "vulnerable",
+            (params.hide_vulnerable == Some(true)).then_some("hide"),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| Some((name.to_string(), value?.to_string())))
+    .collect()
+}
+
+/// The `instances-finder://search` link that runs the search `params`.
+pub fn search_link(params: &FetchParams) -> String {
+    let query = search_query(params);
+    let mut url = Url::parse(&format!("{SCHEME}://search")).expect("valid base link");
+    if !query.is_empty() {
+        url.query_pairs_mut().extend_pairs(&query);
+    }
+    url.to_string()
+}
+
+// A bare host name: no scheme, path, port or credentials to strip
+fn strict_domain(domain: &str) -> Result<String, Error> {
+    let plain = !domain.is_empty()
+        && domain
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'-');
+    let normalized = probe::normalize_domain(domain).ok().filter(|_| plain);
+    normalized.ok_or_else(|| Error::InvalidDomain {
+        domain: domain.to_string(),
+    })
+}
@@ -0,0 +1,77 @@
+//! Search filters and the instance record shared by every front end.
+
+use serde::{Deserialize, Serialize};
+
+use crate::accessibility::AccessibilityReport;
+use crate::activity::ActivityReport;
+use crate::advisories::VulnerabilityReport;
+use crate::moderation::ModerationReport;
+use crate::nodeinfo::SoftwareInfo;
+use crate::notices::ServiceNotice;
+use crate::privacy::PrivacySummary;
+use crate::security::SecuritySummary;
+
+/// Directory search filters, as sent by the webview. `None` leaves a
+/// filter off.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
+pub struct FetchParams {
+    pub language: Option<String>, // ISO 639 code, or several: "fr,en"
+    pub include_closed: Option<bool>,
+    pub include_down: Option<bool>,
+    pub max: Option<usize>,
+    pub signups: Option<String>,      // "open" | "approval"
+    pub region: Option<String>,       // "eu" | "na" | "other"
+    pub size: Option<String>,         // "small" | "medium" | "large"
+    pub software: Option<String>,     // NodeInfo family, e.g. "glitch-soc"
+    pub min_security: Option<String>, // lowest accepted grade, "A".."F"
+    pub exclude_cdn: Option<bool>,
+    pub exclude_third_party: Option<bool>,
+    pub hide_vulnerable: Option<bool>, // hide unpatched critical advisories
+    pub check_notices: Option<bool>,   // look for shutdown/migration notices
+    pub check_activity: Option<bool>,  // read peers and weekly activity
+}
+
+/// An instance as shown in the results, with whatever probes added.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JsInstance {
+    pub domain: String,
+    pub description: String,
+    pub languages: Vec<String>,
+    pub signups: String, // "open" | "approval" | "closed"
+    pub size: u8,
+    #[serde(rename = "sizeLabel")]
+    pub size_label: String,
+    pub region: String,
+    pub availability: f32,
+    /// Registered accounts when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub users: Option<u64>,
+    /// Accounts active in the last 30 days, when the server shares them.
+    #[serde(
+        rename = "activeMonth",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub active_month: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moderation: Option<ModerationReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessibility: Option<AccessibilityReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub software: Option<SoftwareInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security: Option<SecuritySummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<PrivacySummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vulnerability: Option<VulnerabilityReport>,
+    /// Shutdown, migration or read-only notice published by the instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notice: Option<ServiceNotice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity: Option<ActivityReport>,
+    /// Read from the instance itself rather than from the directory, whose
+    /// description, languages and sign-up mode can differ.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub probed: bool,
+}
@@ -0,0 +1,107 @@
+//! Public rules and domain blocks, and the moderation style they suggest.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::probe::ProbeClient;
+
+/// Moderation transparency summary for one instance.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerationReport {
+    /// True when at least the rules or the domain blocks are public.
+    pub public: bool,
+    pub rules_public: bool,
+    pub rules_count: usize,
+    pub rules: Vec<String>,
+    pub domain_blocks_public: bool,
+    pub suspended: usize,
+    pub silenced: usize,
+    pub style: String, // "open" | "balanced" | "strict" | "unknown"
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Rule {
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DomainBlock {
+    severity: String, // "silence" | "suspend"
+}
+
+/// Fetch the public rules of an instance, `None` when they are not shared.
+pub(crate) fn fetch_rules(client: &ProbeClient) -> Result<Option<Vec<Rule>>, Error> {
+    client.get_public("/api/v1/instance/rules", &[])
+}
+
+pub fn moderation_report(client: &ProbeClient) -> Result<ModerationReport, Error> {
+    let rules = fetch_rules(client)?;
+    let blocks: Option<Vec<DomainBlock>> =
+        client.get_public("/api/v1/instance/domain_blocks", &[])?;
+
+    let rules_public = rules.is_some();
+    let rules: Vec<String> = rules
+        .unwrap_or_default()
+        .into_iter()
+        .map(|r| r.text.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    let domain_blocks_public = blocks.is_some();
+    let blocks = blocks.unwrap_or_default();
+    let suspended = blocks.iter().filter(|b| b.severity == "suspend").count();
+    let silenced = blocks.iter().filter(|b| b.severity == "silence").count();
+
+    let public = rules_public || domain_blocks_public;
+    let style = if public {
+        moderation_style(rules.len(), domain_blocks_public, suspended + silenced)
+    } else {
+        "unknown"
+    };
+
+    Ok(ModerationReport {
+        public,
+        rules_public,
+        rules_count: rules.len(),
+        style: style.into(),
+        rules,
+        domain_blocks_public,
+        suspended,
+        silenced,
+    })
+}
+
+// Heuristic matching the wizard choices: many rules and a long public
+// blocklist read as strict, almost no rules or blocks as open.
+fn moderation_style(rules: usize, blocks_public: bool, blocks: usize) -> &'static str {
+    if rules >= 6 && blocks_public && blocks >= 50 {
+        "strict"
+    } else if rules <= 2 || (blocks_public && blocks < 10) {
+        "open"
+    } else {
+        "balanced"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_rules_and_blocklist_read_as_strict() {
+        assert_eq!(moderation_style(6, true, 50), "strict");
+        assert_eq!(moderation_style(12, true, 400), "strict");
+        // Either threshold missed is not enough
+        assert_eq!(moderation_style(5, true, 50), "balanced");
+        assert_eq!(moderation_style(6, true, 49), "balanced");
+        assert_eq!(moderation_style(6, false, 0), "balanced");
+    }
+
+    #[test]
+    fn few_rules_or_blocks_read_as_open() {
+        assert_eq!(moderation_style(0, false, 0), "open");
+        assert_eq!(moderation_style(2, true, 200), "open");
+        assert_eq!(moderation_style(4, true, 9), "open");
+        assert_eq!(moderation_style(4, true, 10), "balanced");
+    }
+}
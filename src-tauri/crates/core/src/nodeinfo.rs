@@ -0,0 +1,126 @@
+//! Server software and version from NodeInfo.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::probe::ProbeClient;
+
+const SCHEMA_2_1: &str = "http://nodeinfo.diaspora.software/ns/schema/2.1";
+const SCHEMA_2_0: &str = "http://nodeinfo.diaspora.software/ns/schema/2.0";
+
+/// Server software and usage as reported by NodeInfo.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SoftwareInfo {
+    /// Normalized family: "mastodon", "glitch-soc", "hometown", "pleroma"…
+    pub name: String,
+    pub version: String,
+    pub users: Option<u64>,
+    pub active_month: Option<u64>,
+    pub local_posts: Option<u64>,
+    pub open_registrations: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Discovery {
+    #[serde(default)]
+    links: Vec<Link>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Link {
+    rel: String,
+    href: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NodeInfo {
+    software: Software,
+    #[serde(default)]
+    usage: Usage,
+    #[serde(default)]
+    open_registrations: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Software {
+    name: String,
+    #[serde(default)]
+    version: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Usage {
+    #[serde(default)]
+    users: UsageUsers,
+    local_posts: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageUsers {
+    total: Option<u64>,
+    active_month: Option<u64>,
+}
+
+/// Discover and read the NodeInfo 2.1 (or 2.0) document of an instance.
+pub fn fetch_software(client: &ProbeClient) -> Result<SoftwareInfo, Error> {
+    let discovery: Discovery = client
+        .get_public("/.well-known/nodeinfo", &[])?
+        .ok_or(Error::NoNodeinfo)?;
+    let link = [SCHEMA_2_1, SCHEMA_2_0]
+        .iter()
+        .find_map(|schema| discovery.links.iter().find(|l| l.rel == *schema))
+        .ok_or(Error::NodeinfoSchema)?;
+    let info: NodeInfo = client.get_url(&link.href)?.ok_or(Error::NodeinfoMissing)?;
+
+    Ok(SoftwareInfo {
+        name: software_family(&info.software.name, &info.software.version),
+        version: info.software.version,
+        users: info.usage.users.total,
+        active_month: info.usage.users.active_month,
+        local_posts: info.usage.local_posts,
+        open_registrations: info.open_registrations,
+    })
+}
+
+/// Mastodon forks keep `mastodon` as NodeInfo name and tag their version
+/// instead (`4.2.8+glitch`, `4.2.8+hometown-1.1.1`).
+fn software_family(name: &str, version: &str) -> String {
+    let name = name.trim().to_lowercase();
+    let version = version.to_lowercase();
+    if name == "mastodon" {
+        if version.contains("+glitch") {
+            return "glitch-soc".into();
+        }
+        if version.contains("+hometown") {
+            return "hometown".into();
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forks_are_told_apart_by_their_version_tag() {
+        assert_eq!(software_family("mastodon", "4.2.8"), "mastodon");
+        assert_eq!(software_family("mastodon", "4.2.8+glitch"), "glitch-soc");
+        assert_eq!(software_family("Mastodon", "4.2.8+GLITCH"), "glitch-soc");
+        assert_eq!(
+            software_family("mastodon", "4.2.8+hometown-1.1.1"),
+            "hometown"
+        );
+    }
+
+    #[test]
+    fn other_names_are_only_normalized() {
+        assert_eq!(software_family(" Pleroma ", "2.6.0"), "pleroma");
+        assert_eq!(software_family("akkoma", "3.10.0+glitch"), "akkoma");
+        assert_eq!(software_family("", ""), "");
+    }
+}
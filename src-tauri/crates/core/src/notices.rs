@@ -0,0 +1,263 @@
+//! Shutdown, migration and read-only announcements.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::html;
+use crate::probe::ProbeClient;
+
+const EXCERPT_CHARS: usize = 200;
+// How far back to look for a negation ("not shutting down")
+const NEGATION_WINDOW: usize = 16;
+
+// The server itself, as subject or object of a shutdown or read-only
+// phrase. Without it, "registrations will be closed" would read like the
+// end of the service.
+const SERVER: &[&str] = &[
+    "this instance",
+    "this server",
+    "the instance",
+    "the server",
+    "our instance",
+    "our server",
+    "cette instance",
+    "ce serveur",
+    "l'instance",
+    "le serveur",
+    "notre instance",
+    "notre serveur",
+];
+
+// Matched on lowercased plain text with straight apostrophes, after a
+// server ("this instance will shut down")…
+const SHUTDOWN_AFTER: &[&str] = &[
+    "will shut down",
+    "will be shut down",
+    "is shutting down",
+    "will be shutting down",
+    "will close",
+    "will be closed",
+    "is closing",
+    "will go offline",
+    "is going offline",
+    "will be discontinued",
+    "is being sunset",
+    "will be sunset",
+    "fermera",
+    "va fermer",
+    "sera fermée",
+    "sera fermé",
+    "ferme ses portes",
+    "cessera de fonctionner",
+    "s'arrêtera",
+    "va s'arrêter",
+];
+
+// …or before it ("we are shutting down this server")
+const SHUTDOWN_BEFORE: &[&str] = &[
+    "shutdown of",
+    "shutting down",
+    "shut down",
+    "closing down",
+    "sunsetting",
+    "fermeture de",
+    "arrêt de",
+    "fermer",
+];
+
+const SHUTDOWN: &[&str] = &["fermeture du serveur", "arrêt du serveur"];
+
+const MIGRATION: &[&str] = &[
+    "migrating to",
+    "moving to a new",
+    "we are moving",
+    "we're moving",
+    "please migrate",
+    "move your account",
+    "merging with",
+    "migrez votre compte",
+    "déplacez votre compte",
+    "migration vers",
+    "nous déménageons",
+    "déménage vers",
+    "fusion avec",
+];
+
+const READ_ONLY_AFTER: &[&str] = &[
+    "is read-only",
+    "is now read-only",
+    "is in read-only mode",
+    "is now in read-only mode",
+    "will be read-only",
+    "will become read-only",
+    "est en lecture seule",
+    "passe en lecture seule",
+    "sera en lecture seule",
+];
+
+const READ_ONLY: &[&str] = &["read-only mode", "read only mode", "mode lecture seule"];
+
+const NEGATIONS: &[&str] = &["not", "never", "no", "pas", "jamais", "aucune"];
+
+// How far after a phrase to look for what is being closed
+const OBJECT_WINDOW: usize = 40;
+
+// "…will close registrations", "…fermera les inscriptions": sign-ups, not
+// the service
+const REGISTRATIONS: &[&str] = &[
+    "registration",
+    "sign-up",
+    "signup",
+    "sign up",
+    "new users",
+    "new accounts",
+    "inscription",
+    "nouveaux comptes",
+];
+
+/// A notice suggesting the instance is going away or winding down.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceNotice {
+    pub kind: String,   // "shutdown" | "migration" | "read_only"
+    pub source: String, // "announcement" | "description"
+    /// Sentence around the matched phrase, as plain text.
+    pub excerpt: String,
+    pub published_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Announcement {
+    content: String,
+    published_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtendedDescription {
+    content: String,
+    updated_at: Option<String>,
+}
+
+// Mastodon < 4.0 only has the long description inside the v1 instance
+#[derive(Debug, Deserialize)]
+struct InstanceV1 {
+    #[serde(default)]
+    description: String,
+}
+
+/// Look for shutdown, migration or read-only notices in the active
+/// announcements, then in the extended description.
+///
+/// Announcements usually need a user session; when the server refuses
+/// them only the description is checked.
+pub fn detect_notice(client: &ProbeClient) -> Result<Option<ServiceNotice>, Error> {
+    let announcements: Vec<Announcement> = client
+        .get_public("/api/v1/announcements", &[])
+        .unwrap_or_default()
+        .unwrap_or_default();
+    for a in announcements {
+        if let Some((kind, excerpt)) = classify(&html::to_plain_text(&a.content)) {
+            return Ok(Some(ServiceNotice {
+                kind: kind.into(),
+                source: "announcement".into(),
+                excerpt,
+                published_at: a.published_at,
+            }));
+        }
+    }
+
+    let (content, updated_at) = match client
+        .get_public::<ExtendedDescription>("/api/v1/instance/extended_description", &[])?
+    {
+        Some(d) => (d.content, d.updated_at),
+        None => {
+            let v1: Option<InstanceV1> = client.get_public("/api/v1/instance", &[])?;
+            (v1.map(|i| i.description).unwrap_or_default(), None)
+        }
+    };
+    Ok(
+        classify(&html::to_plain_text(&content)).map(|(kind, excerpt)| ServiceNotice {
+            kind: kind.into(),
+            source: "description".into(),
+            excerpt,
+            published_at: updated_at,
+        }),
+    )
+}
+
+/// Kind of notice and excerpt for the first non-negated phrase found;
+/// shutdowns win over migrations, which win over read-only mode.
+pub fn classify(text: &str) -> Option<(&'static str, String)> {
+    let normalized = text.to_lowercase().replace(['\u{2019}', '\u{2018}'], "'");
+    [
+        (
+            "shutdown",
+            about_server(SHUTDOWN_BEFORE, SHUTDOWN_AFTER, SHUTDOWN),
+        ),
+        ("migration", about_server(&[], &[], MIGRATION)),
+        ("read_only", about_server(&[], READ_ONLY_AFTER, READ_ONLY)),
+    ]
+    .iter()
+    .find_map(|(kind, phrases)| {
+        phrases
+            .iter()
+            .find_map(|phrase| find_affirmative(&normalized, phrase))
+            .map(|at| (*kind, excerpt(text, &normalized, at)))
+    })
+}
+
+// `before` and `after` joined to each name of the server, plus `alone`
+fn about_server(before: &[&str], after: &[&str], alone: &[&str]) -> Vec<String> {
+    let around = SERVER.iter().flat_map(|server| {
+        before
+            .iter()
+            .map(move |verb| format!("{verb} {server}"))
+            .chain(after.iter().map(move |verb| format!("{server} {verb}")))
+    });
+    around.chain(alone.iter().map(|p| p.to_string())).collect()
+}
+
+fn find_affirmative(text: &str, phrase: &str) -> Option<usize> {
+    text.match_indices(phrase).map(|(at, _)| at).find(|&at| {
+        let mut start = at.saturating_sub(NEGATION_WINDOW);
+        while !text.is_char_boundary(start) {
+            start -= 1;
+        }
+        let negated = text[start..at]
+            .split(|c: char| !c.is_alphanumeric() && c != '\'')
+            .any(|word| NEGATIONS.contains(&word) || word.ends_with("n't"));
+        let after = at + phrase.len();
+        let mut end = (after + OBJECT_WINDOW).min(text.len());
+        while !text.is_char_boundary(end) {
+            end += 1;
+        }
+        let object = text[after..end].split(['.', '!', '?', '\n']).next();
+        let registrations = object.is_some_and(|o| REGISTRATIONS.iter().any(|r| o.contains(r)));
+        !negated && !registrations
+    })
+}
+
+/// The sentence of `original` containing byte offset `at` of `normalized`.
+/// Lowercasing may shift offsets for a few scripts, so map through chars.
+fn excerpt(original: &str, normalized: &str, at: usize) -> String {
+    let char_at = normalized[..at].chars().count();
+    let chars: Vec<char> = original.chars().collect();
+    let char_at = char_at.min(chars.len());
+    let is_end = |c: &char| matches!(c, '.' | '!' | '?' | '\n');
+    let start = chars[..char_at]
+        .iter()
+        .rposition(is_end)
+        .map_or(0, |i| i + 1);
+    let end = chars[char_at..]
+        .iter()
+        .position(is_end)
+        .map_or(chars.len(), |i| char_at + i + 1);
+    let sentence: String = chars[start..end].iter().collect();
+    let sentence = sentence.trim();
+    if sentence.chars().count() > EXCERPT_CHARS {
+        let cut: String = sentence.chars().take(EXCERPT_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        sentence.to_string()
+    }
+}
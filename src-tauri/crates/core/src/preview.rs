@@ -0,0 +1,207 @@
+//! Recent public posts and trending tags, as plain text.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::html;
+use crate::probe::ProbeClient;
+
+const SAMPLE_POSTS: u32 = 10;
+const TRENDING_TAGS: u32 = 10;
+
+/// A glimpse of an instance's culture: recent local posts and trends.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstancePreview {
+    pub domain: String,
+    /// False when the local timeline needs a login.
+    pub timeline_public: bool,
+    pub posts: Vec<PreviewPost>,
+    pub trending_tags: Vec<TrendingTag>,
+}
+
+/// A post as plain text; media descriptions are appended to `text`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewPost {
+    pub author: String,
+    pub created_at: String,
+    pub language: Option<String>,
+    /// Set for content-warning posts: show it and keep `text` collapsed.
+    pub content_warning: Option<String>,
+    pub text: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendingTag {
+    pub name: String,
+    /// Posts using the tag over the last week.
+    pub uses: u64,
+    pub accounts: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Status {
+    created_at: String,
+    language: Option<String>,
+    #[serde(default)]
+    spoiler_text: String,
+    #[serde(default)]
+    sensitive: bool,
+    content: String,
+    url: Option<String>,
+    account: Account,
+    #[serde(default)]
+    media_attachments: Vec<MediaAttachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Account {
+    acct: String,
+    #[serde(default)]
+    display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaAttachment {
+    #[serde(rename = "type")]
+    kind: String,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tag {
+    name: String,
+    #[serde(default)]
+    history: Vec<TagHistory>,
+}
+
+// Mastodon serializes these counters as strings
+#[derive(Debug, Deserialize)]
+struct TagHistory {
+    uses: String,
+    accounts: String,
+}
+
+/// Words added to post text, in the reader's language.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewLabels {
+    pub image: String,
+    pub video: String,
+    pub gif: String,
+    pub audio: String,
+    /// Media without alt text; `{kind}` is replaced by the media kind.
+    pub no_description: String,
+    /// Content warning of a post marked sensitive without CW text.
+    pub sensitive: String,
+    /// Marks links in post text: `[link: example.org]`.
+    pub link: String,
+}
+
+impl Default for PreviewLabels {
+    fn default() -> Self {
+        Self {
+            image: "Image".into(),
+            video: "Video".into(),
+            gif: "GIF".into(),
+            audio: "Audio".into(),
+            no_description: "{kind} without description".into(),
+            sensitive: "Sensitive content".into(),
+            link: "link".into(),
+        }
+    }
+}
+
+pub fn preview(client: &ProbeClient, labels: &PreviewLabels) -> Result<InstancePreview, Error> {
+    let statuses: Option<Vec<Status>> = client.get_public(
+        "/api/v1/timelines/public",
+        &[
+            ("local", "true".into()),
+            ("limit", SAMPLE_POSTS.to_string()),
+        ],
+    )?;
+    let timeline_public = statuses.is_some();
+    let posts = statuses
+        .unwrap_or_default()
+        .into_iter()
+        .map(|status| to_preview_post(status, labels))
+        .collect();
+
+    // Trends can be disabled by the admin; that is not an error
+    let tags: Vec<Tag> = client
+        .get_public(
+            "/api/v1/trends/tags",
+            &[("limit", TRENDING_TAGS.to_string())],
+        )?
+        .unwrap_or_default();
+    let trending_tags = tags
+        .into_iter()
+        .map(|t| TrendingTag {
+            name: t.name,
+            uses: t
+                .history
+                .iter()
+                .filter_map(|h| h.uses.parse::<u64>().ok())
+                .sum(),
+            accounts: t
+                .history
+                .iter()
+                .filter_map(|h| h.accounts.parse::<u64>().ok())
+                .sum(),
+        })
+        .collect();
+
+    Ok(InstancePreview {
+        domain: client.domain().to_string(),
+        timeline_public,
+        posts,
+        trending_tags,
+    })
+}
+
+fn to_preview_post(status: Status, labels: &PreviewLabels) -> PreviewPost {
+    let mut text = html::to_readable_text(&status.content, &labels.link);
+    for media in &status.media_attachments {
+        let kind = match media.kind.as_str() {
+            "video" => &labels.video,
+            "gifv" => &labels.gif,
+            "audio" => &labels.audio,
+            _ => &labels.image,
+        };
+        let line = match media.description.as_deref().map(str::trim) {
+            Some(alt) if !alt.is_empty() => format!("[{kind}: {alt}]"),
+            _ => format!("[{}]", labels.no_description.replace("{kind}", kind)),
+        };
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&line);
+    }
+
+    let spoiler = status.spoiler_text.trim();
+    // A sensitive post without CW text still hides its media in Mastodon
+    let content_warning = if !spoiler.is_empty() {
+        Some(spoiler.to_string())
+    } else if status.sensitive {
+        Some(labels.sensitive.clone())
+    } else {
+        None
+    };
+    let display_name = status.account.display_name.trim();
+    let author = if display_name.is_empty() {
+        format!("@{}", status.account.acct)
+    } else {
+        format!("{} (@{})", display_name, status.account.acct)
+    };
+
+    PreviewPost {
+        author,
+        created_at: status.created_at,
+        language: status.language,
+        content_warning,
+        text,
+        url: status.url,
+    }
+}
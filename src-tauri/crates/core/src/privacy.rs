@@ -0,0 +1,248 @@
+//! CDN fronting and third-party requests made by a server's pages.
+
+use reqwest::header::{HeaderMap, SERVER, VIA};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::html;
+use crate::probe::ProbeClient;
+
+// Response headers that give away a CDN in front of the instance
+const CDN_HEADERS: &[(&str, &str)] = &[
+    ("cf-ray", "Cloudflare"),
+    ("x-amz-cf-id", "Amazon CloudFront"),
+    ("x-fastly-request-id", "Fastly"),
+    ("x-akamai-request-id", "Akamai"),
+    ("cdn-pullzone", "Bunny"),
+    ("x-sucuri-id", "Sucuri"),
+    ("x-azure-ref", "Azure Front Door"),
+];
+
+// `server` / `via` values of the same providers
+const CDN_SERVERS: &[(&str, &str)] = &[
+    ("cloudflare", "Cloudflare"),
+    ("cloudfront", "Amazon CloudFront"),
+    ("akamaighost", "Akamai"),
+    ("bunnycdn", "Bunny"),
+    ("sucuri", "Sucuri"),
+];
+
+/// What a visit to the instance's landing page reveals to third parties.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacySummary {
+    /// CDN provider fronting the instance, if one was recognized.
+    pub cdn: Option<String>,
+    /// Raw `server` header, for the curious.
+    pub server: Option<String>,
+    /// Hosts outside the instance's site that the landing page loads
+    /// scripts, styles or images from.
+    pub third_party_origins: Vec<String>,
+}
+
+impl PrivacySummary {
+    pub fn fronted_by_cdn(&self) -> bool {
+        self.cdn.is_some()
+    }
+
+    pub fn has_third_party_requests(&self) -> bool {
+        !self.third_party_origins.is_empty()
+    }
+}
+
+/// Inspect the landing page headers and the origins of its resources.
+pub fn privacy_summary(client: &ProbeClient) -> Result<PrivacySummary, Error> {
+    let (headers, body) = client.get_page("/")?;
+    let domain = client.domain();
+
+    let mut third_party_origins: Vec<String> = Vec::new();
+    for url in html::resource_urls(&body) {
+        let Some(host) = url_host(&url) else {
+            continue;
+        };
+        if !same_site(&host, domain) && !third_party_origins.contains(&host) {
+            third_party_origins.push(host);
+        }
+    }
+    third_party_origins.sort();
+
+    Ok(PrivacySummary {
+        cdn: detect_cdn(&headers),
+        server: header_str(&headers, SERVER.as_str()).map(str::to_string),
+        third_party_origins,
+    })
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+// Fastly names its caches `cache-<pop>` in `x-served-by` and adds
+// `fastly-*` headers. A bare `server: varnish` is no sign of it: many
+// instances run their own Varnish.
+fn is_fastly(headers: &HeaderMap) -> bool {
+    headers
+        .keys()
+        .any(|name| name.as_str().starts_with("fastly-"))
+        || header_str(headers, "x-served-by").is_some_and(|v| {
+            v.split(',')
+                .any(|cache| cache.trim().to_ascii_lowercase().starts_with("cache-"))
+        })
+}
+
+fn detect_cdn(headers: &HeaderMap) -> Option<String> {
+    if let Some((_, provider)) = CDN_HEADERS.iter().find(|(h, _)| headers.contains_key(*h)) {
+        return Some(provider.to_string());
+    }
+    if is_fastly(headers) {
+        return Some("Fastly".into());
+    }
+    let served_by = [SERVER.as_str(), VIA.as_str()]
+        .iter()
+        .filter_map(|h| header_str(headers, h))
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+    CDN_SERVERS
+        .iter()
+        .find(|(needle, _)| served_by.contains(needle))
+        .map(|(_, provider)| provider.to_string())
+}
+
+/// Host of an absolute or protocol-relative URL; relative URLs are
+/// first-party and yield `None`.
+fn url_host(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .or_else(|| url.strip_prefix("//"))?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then(|| host.trim_end_matches('.').to_ascii_lowercase())
+}
+
+/// Whether `host` belongs to the instance: the domain itself, one of its
+/// subdomains, or a sibling under the same parent (`files.example.org`
+/// for `social.example.org`). Without a public suffix list, parents that
+/// look like `co.uk` are not trusted as a shared site.
+fn same_site(host: &str, domain: &str) -> bool {
+    let under = |parent: &str| host == parent || host.ends_with(&format!(".{parent}"));
+    if under(domain) {
+        return true;
+    }
+    let Some((_, parent)) = domain.split_once('.') else {
+        return false;
+    };
+    let registrable = parent.contains('.') && parent.split('.').next().is_some_and(|l| l.len() > 3);
+    registrable && under(parent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| {
+                (
+                    HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                    HeaderValue::from_str(value).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn cdn(pairs: &[(&str, &str)]) -> Option<String> {
+        detect_cdn(&headers(pairs))
+    }
+
+    #[test]
+    fn recognizes_each_cdn_header() {
+        for (header, provider) in CDN_HEADERS {
+            assert_eq!(cdn(&[(header, "1")]).as_deref(), Some(*provider));
+        }
+        assert_eq!(
+            cdn(&[("CF-Ray", "8a1b2c3d4e5f-CDG"), ("server", "nginx")]).as_deref(),
+            Some("Cloudflare")
+        );
+    }
+
+    #[test]
+    fn recognizes_each_cdn_server_name() {
+        for (needle, provider) in CDN_SERVERS {
+            assert_eq!(cdn(&[("server", needle)]).as_deref(), Some(*provider));
+        }
+        assert_eq!(cdn(&[("server", "AkamaiGHost")]).as_deref(), Some("Akamai"));
+        assert_eq!(
+            cdn(&[("via", "1.1 abc.cloudfront.net (CloudFront)")]).as_deref(),
+            Some("Amazon CloudFront")
+        );
+    }
+
+    #[test]
+    fn recognizes_fastly_by_its_own_headers_only() {
+        let fastly = headers(&[("x-served-by", "cache-par-lfpg1960071-PAR")]);
+        assert!(is_fastly(&fastly));
+        assert_eq!(detect_cdn(&fastly).as_deref(), Some("Fastly"));
+        assert!(is_fastly(&headers(&[(
+            "x-served-by",
+            "cache-iad-kiad7000, cache-cdg20741-CDG"
+        )])));
+        assert!(is_fastly(&headers(&[("fastly-debug-digest", "abc")])));
+
+        // A self-hosted Varnish is no CDN
+        let varnish = headers(&[("server", "Varnish"), ("via", "1.1 varnish (Varnish/7.4)")]);
+        assert!(!is_fastly(&varnish));
+        assert_eq!(detect_cdn(&varnish), None);
+        assert!(!is_fastly(&headers(&[("x-served-by", "web-1")])));
+    }
+
+    #[test]
+    fn plain_servers_have_no_cdn() {
+        assert_eq!(cdn(&[]), None);
+        assert_eq!(cdn(&[("server", "nginx/1.25.3")]), None);
+        assert_eq!(cdn(&[("server", "Mastodon")]), None);
+    }
+
+    #[test]
+    fn same_site_covers_the_domain_its_subdomains_and_siblings() {
+        assert!(same_site("mastodon.social", "mastodon.social"));
+        assert!(same_site("files.mastodon.social", "mastodon.social"));
+        assert!(same_site("files.example.org", "social.example.org"));
+        assert!(same_site("cdn.files.example.org", "social.example.org"));
+        assert!(same_site("example.org", "social.example.org"));
+    }
+
+    #[test]
+    fn same_site_rejects_lookalikes_and_shared_suffixes() {
+        assert!(!same_site("evilmastodon.social", "mastodon.social"));
+        assert!(!same_site(
+            "mastodon.social.evil.example",
+            "mastodon.social"
+        ));
+        assert!(!same_site("mastodon.socia", "mastodon.social"));
+        assert!(!same_site("cdn.other.org", "social.example.org"));
+        // `co.uk` and `com.br` are no shared site
+        assert!(!same_site("shop.co.uk", "social.co.uk"));
+        assert!(!same_site("tracker.com.br", "masto.com.br"));
+        // A bare TLD neither
+        assert!(!same_site("other.social", "mastodon.social"));
+    }
+
+    #[test]
+    fn url_host_skips_relative_urls() {
+        assert_eq!(
+            url_host("https://User@CDN.Example.com:8443/a.js?x#y").as_deref(),
+            Some("cdn.example.com")
+        );
+        assert_eq!(
+            url_host("//fonts.example.net/css").as_deref(),
+            Some("fonts.example.net")
+        );
+        assert_eq!(url_host("/packs/app.js"), None);
+        assert_eq!(url_host("data:image/png;base64,AAAA"), None);
+        assert_eq!(url_host("https:///nohost"), None);
+    }
+}
@@ -0,0 +1,290 @@
+//! HTTP client bound to one server, shared by the probes.
+
+use std::{
+    net::{SocketAddr, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use reqwest::{
+    blocking::{Client as HttpClient, RequestBuilder},
+    header::{HeaderMap, LOCATION},
+    redirect::Policy,
+    StatusCode, Url,
+};
+use serde::de::DeserializeOwned;
+
+use crate::error::Error;
+use crate::USER_AGENT;
+
+const PROBE_TIMEOUT_SECS: u64 = 10;
+
+/// Validate and normalize a user or directory supplied hostname.
+///
+/// Accepts a bare domain or a URL (`https://Example.social/about`), and
+/// returns the lowercase hostname without scheme, path, port or trailing dot.
+/// Internationalized names are returned in their ASCII (`xn--`) form.
+pub fn normalize_domain(input: &str) -> Result<String, Error> {
+    let mut d = input.trim();
+    for scheme in ["https://", "http://"] {
+        if d.len() >= scheme.len() && d[..scheme.len()].eq_ignore_ascii_case(scheme) {
+            d = &d[scheme.len()..];
+        }
+    }
+    let d = d.split(['/', '?', '#']).next().unwrap_or_default();
+    let d = d.rsplit('@').next().unwrap_or_default();
+    let d = d.split(':').next().unwrap_or_default();
+    let d = d.strip_suffix('.').unwrap_or(d);
+
+    let invalid = || Error::InvalidDomain {
+        domain: input.trim().to_string(),
+    };
+    let d = if d.is_ascii() {
+        d.to_ascii_lowercase()
+    } else {
+        // The URL parser applies the IDNA mapping and Punycode
+        Url::parse(&format!("https://{d}/"))
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .ok_or_else(invalid)?
+    };
+    if d.is_empty() || d.len() > 253 {
+        return Err(invalid());
+    }
+    let labels: Vec<&str> = d.split('.').collect();
+    if labels.len() < 2 {
+        return Err(invalid());
+    }
+    for label in &labels {
+        let valid = !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid {
+            return Err(invalid());
+        }
+    }
+    // An all-numeric TLD means an IP address, not a server name
+    if labels
+        .last()
+        .is_some_and(|tld| tld.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(invalid());
+    }
+    Ok(d)
+}
+
+/// Small HTTP client for the public endpoints of a single instance.
+pub struct ProbeClient {
+    http: HttpClient,
+    domain: String,
+    deadline: Option<Instant>,
+}
+
+impl ProbeClient {
+    pub fn new(domain: &str) -> Result<Self, Error> {
+        let domain = normalize_domain(domain)?;
+        let http = HttpClient::builder()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(PROBE_TIMEOUT_SECS))
+            .build()?;
+        Ok(Self {
+            http,
+            domain,
+            deadline: None,
+        })
+    }
+
+    /// Client whose requests must all complete within `budget`, so one
+    /// slow host cannot hold a batch worker for long.
+    pub fn with_deadline(domain: &str, budget: Duration) -> Result<Self, Error> {
+        let mut client = Self::new(domain)?;
+        client.deadline = Some(Instant::now() + budget);
+        Ok(client)
+    }
+
+    /// Normalized hostname this client talks to.
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// GET a JSON document that admins may keep private.
+    ///
+    /// Returns `Ok(None)` when the server refuses to share it (401, 403,
+    /// 404, 410 or 422, depending on the software and its settings).
+    pub fn get_public<T>(&self, path: &str, query: &[(&str, String)]) -> Result<Option<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        self.fetch(&format!("https://{}{}", self.domain, path), query)
+    }
+
+    /// Same as [`Self::get_public`] for an absolute URL advertised by the
+    /// instance itself (e.g. NodeInfo links); only HTTPS is followed.
+    pub fn get_url<T>(&self, url: &str) -> Result<Option<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        if !url.starts_with("https://") {
+            return Err(Error::NotHttps {
+                domain: self.domain.clone(),
+                url: url.to_string(),
+            });
+        }
+        self.fetch(url, &[])
+    }
+
+    /// Headers and body of an HTML page on the instance (redirects followed).
+    pub fn get_page(&self, path: &str) -> Result<(HeaderMap, String), Error> {
+        let url = format!("https://{}{}", self.domain, path);
+        let response = self.with_deadline_timeout(self.http.get(&url))?.send()?;
+        if !response.status().is_success() {
+            return Err(http_status(&url, response.status()));
+        }
+        let headers = response.headers().clone();
+        let body = response.text()?;
+        Ok((headers, body))
+    }
+
+    /// Time a request to the instance API; any non-success status is a
+    /// failure, like a timeout or connection error.
+    pub fn ping(&self) -> Result<Duration, Error> {
+        let url = format!("https://{}/api/v1/instance", self.domain);
+        let started = Instant::now();
+        let response = self.with_deadline_timeout(self.http.get(&url))?.send()?;
+        if !response.status().is_success() {
+            return Err(http_status(&url, response.status()));
+        }
+        Ok(started.elapsed())
+    }
+
+    /// Where `url` redirects to, without following it.
+    pub fn redirect_location(&self, url: &str) -> Result<Option<String>, Error> {
+        let http = HttpClient::builder()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(PROBE_TIMEOUT_SECS))
+            .redirect(Policy::none())
+            .build()?;
+        let response = self.with_deadline_timeout(http.get(url))?.send()?;
+        if !response.status().is_redirection() {
+            return Ok(None);
+        }
+        Ok(response
+            .headers()
+            .get(LOCATION)
+            .and_then(|l| l.to_str().ok())
+            .map(str::to_string))
+    }
+
+    /// How long the next network step may take: the usual probe timeout,
+    /// shortened by the deadline when there is one.
+    pub fn time_left(&self) -> Result<Duration, Error> {
+        let timeout = Duration::from_secs(PROBE_TIMEOUT_SECS);
+        let Some(deadline) = self.deadline else {
+            return Ok(timeout);
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(Error::TimedOut {
+                domain: self.domain.clone(),
+            });
+        }
+        Ok(remaining.min(timeout))
+    }
+
+    /// Addresses of the instance for `port`. The system resolver has no
+    /// timeout of its own, so the lookup runs on a helper thread that is
+    /// given up on after [`Self::time_left`].
+    pub fn resolve(&self, port: u16) -> Result<Vec<SocketAddr>, Error> {
+        let timeout = self.time_left()?;
+        let (tx, rx) = mpsc::channel();
+        let host = (self.domain.clone(), port);
+        thread::spawn(move || {
+            let _ = tx.send(host.to_socket_addrs().map(Iterator::collect::<Vec<_>>));
+        });
+        match rx.recv_timeout(timeout) {
+            Ok(Ok(addrs)) if !addrs.is_empty() => Ok(addrs),
+            Ok(_) => Err(Error::NoResolve {
+                domain: self.domain.clone(),
+            }),
+            Err(_) => Err(Error::TimedOut {
+                domain: self.domain.clone(),
+            }),
+        }
+    }
+
+    fn with_deadline_timeout(&self, request: RequestBuilder) -> Result<RequestBuilder, Error> {
+        if self.deadline.is_none() {
+            return Ok(request);
+        }
+        Ok(request.timeout(self.time_left()?))
+    }
+
+    fn fetch<T>(&self, url: &str, query: &[(&str, String)]) -> Result<Option<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let mut request = self.with_deadline_timeout(self.http.get(url))?;
+        if !query.is_empty() {
+            request = request.query(&query);
+        }
+        let response = request.send()?;
+
+        let status = response.status();
+        if matches!(
+            status,
+            StatusCode::UNAUTHORIZED
+                | StatusCode::FORBIDDEN
+                | StatusCode::NOT_FOUND
+                | StatusCode::GONE
+                | StatusCode::UNPROCESSABLE_ENTITY
+        ) {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(http_status(url, status));
+        }
+        Ok(Some(response.json()?))
+    }
+}
+
+pub(crate) fn http_status(url: &str, status: StatusCode) -> Error {
+    Error::HttpStatus {
+        url: url.to_string(),
+        status: status.to_string(),
+    }
+}
+
+/// Run `f` over `items` on at most `workers` threads, keeping input order.
+pub fn parallel_map<T, R, F>(items: &[T], workers: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else {
+                    break;
+                };
+                let result = f(item);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.expect("every item is processed"))
+        .collect()
+}
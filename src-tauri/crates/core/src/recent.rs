@@ -0,0 +1,82 @@
+//! Searches run lately, newest first, kept as `instances-finder://search`
+//! links so running one again goes through the same path as any link.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::link;
+use crate::model::FetchParams;
+use crate::store::{self, now};
+
+const FORMAT_VERSION: u32 = 1;
+pub const MAX_RECENT: usize = 10;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentSearch {
+    pub link: String,
+    pub searched_at: u64,
+}
+
+impl RecentSearch {
+    /// The search options of the link (`lang`, `size`…), in link order.
+    pub fn query(&self) -> Vec<(String, String)> {
+        reqwest::Url::parse(&self.link)
+            .map(|url| url.query_pairs().into_owned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// The last [`MAX_RECENT`] distinct searches.
+pub struct RecentStore {
+    path: PathBuf,
+}
+
+impl RecentStore {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    pub fn list(&self) -> Result<Vec<RecentSearch>, Error> {
+        let _guard = store::lock()?;
+        self.load()
+    }
+
+    /// Put the search `params` first, dropping an older copy of it and the
+    /// oldest searches past [`MAX_RECENT`].
+    pub fn record(&self, params: &FetchParams) -> Result<Vec<RecentSearch>, Error> {
+        let link = link::search_link(params);
+        let _guard = store::lock()?;
+        let mut items = self.load()?;
+        items.retain(|i| i.link != link);
+        items.insert(
+            0,
+            RecentSearch {
+                link,
+                searched_at: now(),
+            },
+        );
+        items.truncate(MAX_RECENT);
+        self.save(&items)?;
+        Ok(items)
+    }
+
+    pub fn clear(&self) -> Result<(), Error> {
+        let _guard = store::lock()?;
+        self.save(&[])
+    }
+
+    // Refuse a corrupt file or one written by a newer version rather than
+    // overwrite it
+    fn load(&self) -> Result<Vec<RecentSearch>, Error> {
+        store::read(&self.path, FORMAT_VERSION)
+    }
+
+    fn save(&self, items: &[RecentSearch]) -> Result<(), Error> {
+        store::write(&self.path, FORMAT_VERSION, &items)
+    }
+}
@@ -0,0 +1,132 @@
+//! Relevance score and ranking; mirrors `src/lib/score.ts`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::JsInstance;
+
+/// Wizard preferences, as sent by the webview (`src/types.ts`).
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Preferences {
+    pub languages: Vec<String>,
+    pub size: String,       // "any" | "small" | "medium" | "large"
+    pub moderation: String, // "any" | "open" | "balanced" | "strict"
+    pub signups: String,    // "any" | "open" | "approval"
+    pub region: String,     // "any" | "eu" | "na" | "other"
+    pub nsfw: String,       // "any" | "allowed" | "limited"
+    pub software: String,   // "any" | NodeInfo family
+    pub security: String,   // "any" | minimum grade "A" | "B" | "C"
+    pub privacy: String,    // "any" | "no_cdn" | "no_third_party" | "strict"
+    pub vulnerable: String, // "any" (ranked lower) | "hide"
+    pub sort: String,       // "relevance" | "activity" | "growth" | "peers"
+}
+
+fn chosen(value: &str) -> Option<&str> {
+    (!value.is_empty() && value != "any").then_some(value)
+}
+
+/// Relevance score of an instance; keep in sync with `src/lib/score.ts`.
+pub fn score_instance(i: &JsInstance, p: &Preferences) -> f32 {
+    let mut s = 0.0;
+    // Languages: +2 when one of the chosen languages is listed
+    if !p.languages.is_empty() && i.languages.iter().any(|l| p.languages.contains(l)) {
+        s += 2.0;
+    }
+    // Size: inverse distance to the preferred bucket
+    if let Some(size) = chosen(&p.size) {
+        let wanted: i32 = match size {
+            "small" => 1,
+            "medium" => 2,
+            _ => 3,
+        };
+        s += 2.0 - (wanted - i32::from(i.size)).abs() as f32;
+    }
+    // Moderation: real data for evaluated domains, otherwise the signup mode
+    // (directory results are not probed)
+    if let Some(moderation) = chosen(&p.moderation) {
+        let style = i
+            .moderation
+            .as_ref()
+            .map(|m| m.style.as_str())
+            .filter(|style| *style != "unknown");
+        match style {
+            Some(style) => {
+                if style == moderation {
+                    s += 1.0;
+                }
+            }
+            None => {
+                let open = i.signups == "open";
+                let approval = i.signups == "approval";
+                if (moderation == "open" && open) || (moderation != "open" && approval) {
+                    s += 0.5;
+                }
+            }
+        }
+    }
+    if let Some(region) = chosen(&p.region) {
+        if i.region == region {
+            s += 1.0;
+        }
+    }
+    // Measured accessibility (alt text, CamelCase hashtags)
+    if let Some(score) = i.accessibility.as_ref().and_then(|a| a.score) {
+        s += score * 1.5;
+    }
+    // Known-vulnerable versions: the longer a critical fix is pending, the
+    // more the server looks unmaintained
+    if let Some(v) = &i.vulnerability {
+        s -= match v.severity.as_str() {
+            "critical" if v.critical_unpatched_days.unwrap_or(0) > 90 => 4.0,
+            "critical" => 3.0,
+            "high" => 1.5,
+            _ => 0.5,
+        };
+    }
+    // An announced shutdown outweighs everything else
+    if let Some(notice) = &i.notice {
+        s -= match notice.kind.as_str() {
+            "shutdown" => 10.0,
+            "read_only" => 6.0,
+            _ => 4.0,
+        };
+    }
+    // Activity: a big but idle server is not a lively community
+    if let Some(activity) = &i.activity {
+        if activity.logins_per_user.is_some_and(|r| r < 0.02) {
+            s -= 1.0;
+        }
+        match activity.trend_label.as_str() {
+            "growing" => s += 0.5,
+            "declining" => s -= 0.5,
+            _ => {}
+        }
+    }
+    s += i.availability * 1.5;
+    s
+}
+
+/// Value of the optional sort key; instances without it sort last.
+fn sort_key(i: &JsInstance, sort: &str) -> Option<f32> {
+    let activity = i.activity.as_ref()?;
+    match sort {
+        "activity" => activity.weekly_logins,
+        "growth" => activity.trend,
+        "peers" => activity.peers.map(|p| p as f32),
+        _ => None,
+    }
+}
+
+/// Sort by descending score, or by the chosen activity field then score;
+/// ties keep their input order.
+pub fn rank_instances(items: &mut [JsInstance], p: &Preferences) {
+    match p.sort.as_str() {
+        "activity" | "growth" | "peers" => items.sort_by(|a, b| {
+            let (ka, kb) = (sort_key(a, &p.sort), sort_key(b, &p.sort));
+            kb.unwrap_or(f32::NEG_INFINITY)
+                .total_cmp(&ka.unwrap_or(f32::NEG_INFINITY))
+                .then(score_instance(b, p).total_cmp(&score_instance(a, p)))
+        }),
+        _ => items.sort_by(|a, b| score_instance(b, p).total_cmp(&score_instance(a, p))),
+    }
+}
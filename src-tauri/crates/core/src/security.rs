@@ -0,0 +1,424 @@
+//! TLS, security headers and an overall grade for a server.
+
+use std::{fmt, io, net::TcpStream, str::FromStr, sync::Arc, time::SystemTime};
+
+use reqwest::header::{CONTENT_SECURITY_POLICY, STRICT_TRANSPORT_SECURITY};
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{self, CryptoProvider},
+    pki_types::{CertificateDer, ServerName, UnixTime},
+    CertificateError, ClientConfig, ClientConnection, DigitallySignedStruct, ProtocolVersion,
+    SignatureScheme,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::probe::ProbeClient;
+
+// Six months, the usual minimum for HSTS preload lists
+const HSTS_STRONG_MAX_AGE: u64 = 15_552_000;
+const EXPIRY_WARNING_DAYS: i64 = 14;
+
+/// Overall transport security grade, `A` (best) to `F`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Grade {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+}
+
+impl Grade {
+    /// True when `self` is at least as good as `min` (`A` beats `B`).
+    pub fn meets(self, min: Grade) -> bool {
+        self <= min
+    }
+}
+
+impl FromStr for Grade {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "A" => Ok(Grade::A),
+            "B" => Ok(Grade::B),
+            "C" => Ok(Grade::C),
+            "D" => Ok(Grade::D),
+            "E" => Ok(Grade::E),
+            "F" => Ok(Grade::F),
+            _ => Err(Error::InvalidValue {
+                name: "min_security".into(),
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Transport security posture of an instance.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SecuritySummary {
+    pub certificate_valid: bool,
+    /// `certificate_failure` in English, until the caller relabels it.
+    pub certificate_error: Option<String>,
+    #[serde(skip)]
+    pub certificate_failure: Option<Error>,
+    /// Read even from a certificate that failed validation.
+    pub days_until_expiry: Option<i64>,
+    pub tls_version: Option<String>,
+    /// `None` when the home page could not be read (error status, timeout…).
+    pub hsts: Option<bool>,
+    pub hsts_max_age: Option<u64>,
+    /// `None` when the home page could not be read.
+    pub csp: Option<bool>,
+    /// `None` when plain HTTP could not be reached at all.
+    pub http_redirects_to_https: Option<bool>,
+    pub grade: Grade,
+}
+
+struct TlsFacts {
+    version: Option<String>,
+    days_until_expiry: Option<i64>,
+}
+
+enum TlsFailure {
+    /// The server answered but the TLS session was refused: bad or expired
+    /// certificate, obsolete protocol…
+    Rejected(Error),
+    /// Nothing to judge: no address, no connection or no answer in time.
+    Unreachable(Error),
+}
+
+/// Check the certificate, TLS version, security headers and HTTP redirect.
+/// A server that cannot be reached over TLS is an error, not a bad grade;
+/// headers the home page does not let us read are left unknown.
+pub fn audit(client: &ProbeClient) -> Result<SecuritySummary, Error> {
+    let domain = client.domain();
+    let (certificate_failure, tls_facts) = match tls_handshake(client, Verify::Trusted) {
+        Ok(facts) => (None, Some(facts)),
+        // Read the certificate anyway, to tell how long ago it expired
+        Err(TlsFailure::Rejected(e)) => (Some(e), tls_handshake(client, Verify::No).ok()),
+        Err(TlsFailure::Unreachable(e)) => return Err(e),
+    };
+    let certificate_valid = certificate_failure.is_none();
+
+    let mut hsts = None;
+    let mut hsts_max_age = None;
+    let mut csp = None;
+    if certificate_valid {
+        if let Ok((headers, _)) = client.get_page("/") {
+            let value = headers
+                .get(STRICT_TRANSPORT_SECURITY)
+                .and_then(|v| v.to_str().ok());
+            hsts = Some(value.is_some());
+            hsts_max_age = value.and_then(parse_max_age);
+            csp = Some(headers.contains_key(CONTENT_SECURITY_POLICY));
+        }
+    }
+    let http_redirects_to_https = client
+        .redirect_location(&format!("http://{domain}/"))
+        .ok()
+        .map(|location| location.is_some_and(|l| l.starts_with("https://")));
+
+    let tls_version = tls_facts.as_ref().and_then(|t| t.version.clone());
+    let days_until_expiry = tls_facts.as_ref().and_then(|t| t.days_until_expiry);
+    let grade = grade(
+        certificate_valid,
+        days_until_expiry,
+        &[
+            Some(tls_version.as_deref() == Some("TLS 1.3")),
+            hsts.map(|_| hsts_max_age.is_some_and(|age| age >= HSTS_STRONG_MAX_AGE)),
+            csp,
+            Some(http_redirects_to_https == Some(true)),
+        ],
+    );
+
+    Ok(SecuritySummary {
+        certificate_valid,
+        certificate_error: certificate_failure.as_ref().map(Error::to_string),
+        certificate_failure,
+        days_until_expiry,
+        tls_version,
+        hsts,
+        hsts_max_age,
+        csp,
+        http_redirects_to_https,
+        grade,
+    })
+}
+
+// Unknown checks (`None`) are left out: the grade is the share of the
+// known ones that pass
+fn grade(valid: bool, days_until_expiry: Option<i64>, checks: &[Option<bool>]) -> Grade {
+    if !valid || days_until_expiry.is_some_and(|d| d < 0) {
+        return Grade::F;
+    }
+    let known = checks.iter().flatten().count();
+    let passed = checks.iter().flatten().filter(|ok| **ok).count();
+    let mut points = (passed * 4).checked_div(known).unwrap_or(0);
+    // A certificate about to lapse is a maintenance warning sign
+    if days_until_expiry.is_some_and(|d| d < EXPIRY_WARNING_DAYS) {
+        points = points.saturating_sub(1);
+    }
+    match points {
+        4 => Grade::A,
+        3 => Grade::B,
+        2 => Grade::C,
+        1 => Grade::D,
+        _ => Grade::E,
+    }
+}
+
+fn parse_max_age(hsts: &str) -> Option<u64> {
+    hsts.split(';').find_map(|directive| {
+        let (name, value) = directive.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("max-age")
+            .then(|| value.trim().trim_matches('"').parse().ok())
+            .flatten()
+    })
+}
+
+#[derive(Clone, Copy)]
+enum Verify {
+    /// Against the web PKI roots, like a browser.
+    Trusted,
+    /// Accept any certificate, only to read what the server sends.
+    No,
+}
+
+// reqwest does not expose the negotiated protocol nor the peer certificate
+// expiry, so run a separate rustls handshake. Every step fits in the
+// client's deadline, like its HTTP requests.
+fn tls_handshake(client: &ProbeClient, verify: Verify) -> Result<TlsFacts, TlsFailure> {
+    let domain = client.domain();
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| TlsFailure::Unreachable(internal(e)))?;
+    let config = match verify {
+        Verify::Trusted => builder.with_root_certificates(rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        }),
+        Verify::No => builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoVerifier(provider))),
+    }
+    .with_no_client_auth();
+    let server_name = ServerName::try_from(domain.to_string()).map_err(|_| {
+        TlsFailure::Unreachable(Error::InvalidDomain {
+            domain: domain.to_string(),
+        })
+    })?;
+    let mut conn = ClientConnection::new(Arc::new(config), server_name)
+        .map_err(|e| TlsFailure::Unreachable(internal(e)))?;
+
+    let mut sock = connect(client).map_err(TlsFailure::Unreachable)?;
+    while conn.is_handshaking() {
+        let step = client.time_left().and_then(|timeout| {
+            sock.set_read_timeout(Some(timeout))
+                .and_then(|()| sock.set_write_timeout(Some(timeout)))
+                .map_err(|e| network_error(client, &e))
+        });
+        step.map_err(TlsFailure::Unreachable)?;
+        conn.complete_io(&mut sock).map_err(|e| {
+            // rustls reports its own errors wrapped in io::Error
+            match e
+                .get_ref()
+                .and_then(|inner| inner.downcast_ref::<rustls::Error>())
+            {
+                Some(tls) => TlsFailure::Rejected(tls_error(domain, tls)),
+                None => TlsFailure::Unreachable(network_error(client, &e)),
+            }
+        })?;
+    }
+
+    let version = conn.protocol_version().map(|v| match v {
+        ProtocolVersion::TLSv1_3 => "TLS 1.3".to_string(),
+        ProtocolVersion::TLSv1_2 => "TLS 1.2".to_string(),
+        other => format!("{other:?}"),
+    });
+    let days_until_expiry = conn
+        .peer_certificates()
+        .and_then(|certs| certs.first())
+        .and_then(|der| x509_parser::parse_x509_certificate(der).ok())
+        .map(|(_, cert)| {
+            let now = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or_default();
+            (cert.validity().not_after.timestamp() - now).div_euclid(86_400)
+        });
+    Ok(TlsFacts {
+        version,
+        days_until_expiry,
+    })
+}
+
+fn tls_error(domain: &str, e: &rustls::Error) -> Error {
+    let domain = domain.to_string();
+    match e {
+        rustls::Error::InvalidCertificate(cert) => match cert {
+            CertificateError::Expired | CertificateError::ExpiredContext { .. } => {
+                Error::CertificateExpired { domain }
+            }
+            CertificateError::NotValidYet | CertificateError::NotValidYetContext { .. } => {
+                Error::CertificateNotValidYet { domain }
+            }
+            CertificateError::UnknownIssuer => Error::CertificateUntrusted { domain },
+            CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. } => {
+                Error::CertificateWrongName { domain }
+            }
+            CertificateError::Revoked => Error::CertificateRevoked { domain },
+            _ => Error::CertificateInvalid { domain },
+        },
+        _ => Error::TlsHandshake { domain },
+    }
+}
+
+fn internal(e: impl fmt::Display) -> Error {
+    Error::Internal {
+        error: e.to_string(),
+    }
+}
+
+// Signatures are still checked, so the handshake proves the server holds
+// the certificate's key; only the chain and the name are not
+#[derive(Debug)]
+struct NoVerifier(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+// First address that accepts a connection on port 443: a host may publish
+// an IPv6 address it cannot be reached on from here
+fn connect(client: &ProbeClient) -> Result<TcpStream, Error> {
+    let mut last_error = None;
+    for addr in client.resolve(443)? {
+        match TcpStream::connect_timeout(&addr, client.time_left()?) {
+            Ok(sock) => return Ok(sock),
+            Err(e) => last_error = Some(network_error(client, &e)),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| Error::NoResolve {
+        domain: client.domain().to_string(),
+    }))
+}
+
+fn network_error(client: &ProbeClient, e: &io::Error) -> Error {
+    let domain = client.domain().to_string();
+    match e.kind() {
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => Error::TimedOut { domain },
+        _ => Error::Connect { domain },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_grades_a_to_f_only() {
+        assert_eq!(" b ".parse::<Grade>().unwrap(), Grade::B);
+        assert_eq!("F".parse::<Grade>().unwrap(), Grade::F);
+        for bad in ["", "G", "AA", "any", "1"] {
+            assert_eq!(
+                bad.parse::<Grade>().unwrap_err().code(),
+                "invalid_value",
+                "{bad:?}"
+            );
+        }
+        assert!(Grade::A.meets(Grade::B));
+        assert!(Grade::B.meets(Grade::B));
+        assert!(!Grade::C.meets(Grade::B));
+        assert_eq!(serde_json::to_string(&Grade::C).unwrap(), "\"C\"");
+    }
+
+    #[test]
+    fn grades_the_checks_that_could_be_made() {
+        let all = [Some(true); 4];
+        assert_eq!(grade(true, Some(90), &all), Grade::A);
+        assert_eq!(grade(false, Some(90), &all), Grade::F);
+        assert_eq!(grade(true, Some(-1), &all), Grade::F);
+        assert_eq!(grade(true, Some(3), &all), Grade::B);
+        assert_eq!(
+            grade(
+                true,
+                None,
+                &[Some(true), Some(false), Some(true), Some(false)]
+            ),
+            Grade::C
+        );
+        assert_eq!(grade(true, None, &[Some(false); 4]), Grade::E);
+        // Headers unknown: graded on TLS and the redirect alone
+        assert_eq!(
+            grade(true, None, &[Some(true), None, None, Some(true)]),
+            Grade::A
+        );
+        assert_eq!(
+            grade(true, None, &[Some(true), None, None, Some(false)]),
+            Grade::C
+        );
+    }
+
+    #[test]
+    fn reads_hsts_max_age() {
+        assert_eq!(parse_max_age("max-age=31536000"), Some(31_536_000));
+        assert_eq!(
+            parse_max_age("includeSubDomains; Max-Age=\"600\"; preload"),
+            Some(600)
+        );
+        assert_eq!(parse_max_age("includeSubDomains"), None);
+        assert_eq!(parse_max_age("max-age=soon"), None);
+    }
+}
@@ -0,0 +1,33 @@
+//! Where the app keeps its files: the data directory and the names of the
+//! files in it, so other front ends read and write the same ones.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Bundle identifier from `tauri.conf.json`; names the app data directory.
+pub const APP_IDENTIFIER: &str = "org.instances.finder";
+
+pub const CACHE_FILE: &str = "instances_cache.json";
+pub const ADVISORIES_FILE: &str = "advisories.json";
+pub const UPTIME_FILE: &str = "uptime_history.json";
+pub const FAVORITES_FILE: &str = "favorites.json";
+pub const WATCHLIST_FILE: &str = "watchlist.json";
+pub const RECENT_FILE: &str = "recent_searches.json";
+pub const WINDOW_STATE_FILE: &str = "window_state.json";
+
+/// App data directory as resolved by Tauri (`PathResolver::app_data_dir`),
+/// for tools that run without an app handle.
+pub fn default_app_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(APP_IDENTIFIER)
+}
+
+/// `name` inside our folder of `app_data_dir`, creating the folder.
+pub fn data_file(app_data_dir: &Path, name: &str) -> PathBuf {
+    let dir = app_data_dir.join("instances-finder");
+    let _ = fs::create_dir_all(&dir);
+    dir.join(name)
+}
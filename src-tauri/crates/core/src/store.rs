@@ -0,0 +1,69 @@
+//! What the local JSON stores share: the clock, a write lock and the
+//! versioned file format.
+
+use std::{
+    fs, io,
+    path::Path,
+    sync::{Mutex, MutexGuard},
+    time::SystemTime,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::error::Error;
+
+// One writer at a time: commands, the watchlist thread and the browser
+// server all load, change and save whole files
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize)]
+struct FileOut<'a, T> {
+    version: u32,
+    items: &'a T,
+}
+
+#[derive(Deserialize)]
+struct FileIn<T> {
+    version: u32,
+    items: T,
+}
+
+/// Unix seconds.
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Hold while reading or changing a store file.
+pub(crate) fn lock() -> Result<MutexGuard<'static, ()>, Error> {
+    Ok(FILE_LOCK.lock()?)
+}
+
+/// The items of a `{"version": n, "items": …}` file, the default when
+/// there is no file yet. A file that cannot be read or parsed, or that a
+/// newer version wrote, is an error rather than a reason to start over:
+/// saving would lose what it holds.
+pub(crate) fn read<T: DeserializeOwned + Default>(path: &Path, supported: u32) -> Result<T, Error> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => return Err(e.into()),
+    };
+    let file: FileIn<T> = serde_json::from_slice(&bytes)?;
+    if file.version > supported {
+        return Err(Error::FileVersion {
+            path: path.display().to_string(),
+            version: file.version,
+            supported,
+        });
+    }
+    Ok(file.items)
+}
+
+/// Write `items` in the format [`read`] expects.
+pub(crate) fn write<T: Serialize>(path: &Path, version: u32, items: &T) -> Result<(), Error> {
+    let bytes = serde_json::to_vec_pretty(&FileOut { version, items })?;
+    Ok(fs::write(path, bytes)?)
+}
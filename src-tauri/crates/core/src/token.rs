@@ -0,0 +1,41 @@
+//! The instances.social token saved in the system keyring, shared by the
+//! desktop app and the command-line tool.
+
+use keyring::Entry;
+
+use crate::error::Error;
+
+const SERVICE: &str = "org.instances.finder";
+const USERNAME: &str = "instances_social_token";
+
+/// Overrides the keyring in [`find`], for CI runners without a secret
+/// service.
+pub const TOKEN_ENV: &str = "INSTANCES_FINDER_TOKEN";
+
+/// Token saved in the system keyring, if any.
+pub fn load() -> Option<String> {
+    Entry::new(SERVICE, USERNAME).ok()?.get_password().ok()
+}
+
+/// Token from [`TOKEN_ENV`] when set, else the one saved in the keyring.
+pub fn find() -> Option<String> {
+    std::env::var(TOKEN_ENV)
+        .ok()
+        .filter(|t| !t.trim().is_empty())
+        .or_else(load)
+}
+
+pub fn store(token: &str) -> Result<(), Error> {
+    let keyring = |e: keyring::Error| Error::Keyring {
+        error: e.to_string(),
+    };
+    let entry = Entry::new(SERVICE, USERNAME).map_err(keyring)?;
+    entry.set_password(token).map_err(keyring)
+}
+
+pub fn delete() {
+    if let Ok(entry) = Entry::new(SERVICE, USERNAME) {
+        // keyring v3 uses `delete_credential` instead of `delete_password`
+        let _ = entry.delete_credential();
+    }
+}
@@ -0,0 +1,207 @@
+//! Local uptime and latency history per domain.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::error::Error;
+use crate::store::{self, now};
+use serde::{Deserialize, Serialize};
+
+// Earlier files held the bare map; they read as an empty history
+const FORMAT_VERSION: u32 = 1;
+// Samples older than this are dropped on every write
+const RETENTION_DAYS: u64 = 30;
+// Hard cap per domain, in case a caller probes in a loop
+const MAX_SAMPLES: usize = 2000;
+const DAY_SECS: u64 = 86_400;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Sample {
+    /// Unix seconds.
+    pub at: u64,
+    pub ok: bool,
+    pub latency_ms: Option<u32>,
+}
+
+/// Availability measured by this app, as opposed to the directory's.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UptimeStats {
+    pub domain: String,
+    pub samples: usize,
+    pub last_checked: Option<u64>,
+    /// Share of successful probes (0..1).
+    pub uptime_7d: Option<f32>,
+    pub uptime_30d: Option<f32>,
+    /// Latency percentiles of successful probes over 30 days.
+    pub p50_ms: Option<u32>,
+    pub p95_ms: Option<u32>,
+}
+
+/// Probe history of every domain, stored as one JSON file.
+pub struct UptimeStore {
+    path: PathBuf,
+}
+
+impl UptimeStore {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    pub fn record(&self, domain: &str, result: &Result<Duration, Error>) -> Result<(), Error> {
+        let _guard = store::lock()?;
+        let mut history = self.load();
+        let samples = history.entry(domain.to_string()).or_default();
+        samples.push(Sample {
+            at: now(),
+            ok: result.is_ok(),
+            latency_ms: result
+                .as_ref()
+                .ok()
+                .map(|d| u32::try_from(d.as_millis()).unwrap_or(u32::MAX)),
+        });
+        prune(&mut history);
+        self.save(&history)
+    }
+
+    pub fn stats(&self, domain: &str) -> UptimeStats {
+        let history = {
+            let _guard = store::lock();
+            self.load()
+        };
+        let samples = history.get(domain).map(Vec::as_slice).unwrap_or_default();
+        let now = now();
+        let within = |days: u64| {
+            samples
+                .iter()
+                .filter(move |s| s.at + days * DAY_SECS >= now)
+        };
+        let uptime = |days: u64| {
+            let (ok, total) =
+                within(days).fold((0, 0), |(ok, n), s| (ok + usize::from(s.ok), n + 1));
+            (total > 0).then(|| ok as f32 / total as f32)
+        };
+        let mut latencies: Vec<u32> = within(RETENTION_DAYS)
+            .filter_map(|s| s.latency_ms)
+            .collect();
+        latencies.sort_unstable();
+
+        UptimeStats {
+            domain: domain.to_string(),
+            samples: samples.len(),
+            last_checked: samples.last().map(|s| s.at),
+            uptime_7d: uptime(7),
+            uptime_30d: uptime(RETENTION_DAYS),
+            p50_ms: percentile(&latencies, 50),
+            p95_ms: percentile(&latencies, 95),
+        }
+    }
+
+    /// Forget one domain, or everything when `domain` is `None`.
+    pub fn purge(&self, domain: Option<&str>) -> Result<(), Error> {
+        let _guard = store::lock()?;
+        match domain {
+            Some(domain) => {
+                let mut history = self.load();
+                history.remove(domain);
+                self.save(&history)
+            }
+            None if self.path.exists() => Ok(fs::remove_file(&self.path)?),
+            None => Ok(()),
+        }
+    }
+
+    // Only measurements of this app: an unreadable file starts a fresh
+    // history rather than blocking probes
+    fn load(&self) -> BTreeMap<String, Vec<Sample>> {
+        store::read(&self.path, FORMAT_VERSION).unwrap_or_default()
+    }
+
+    fn save(&self, history: &BTreeMap<String, Vec<Sample>>) -> Result<(), Error> {
+        store::write(&self.path, FORMAT_VERSION, history)
+    }
+}
+
+fn prune(history: &mut BTreeMap<String, Vec<Sample>>) {
+    let cutoff = now().saturating_sub(RETENTION_DAYS * DAY_SECS);
+    for samples in history.values_mut() {
+        samples.retain(|s| s.at >= cutoff);
+        if samples.len() > MAX_SAMPLES {
+            samples.drain(..samples.len() - MAX_SAMPLES);
+        }
+    }
+    history.retain(|_, samples| !samples.is_empty());
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[u32], pct: usize) -> Option<u32> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(days_ago: u64) -> Sample {
+        Sample {
+            at: now() - days_ago * DAY_SECS,
+            ok: true,
+            latency_ms: Some(100),
+        }
+    }
+
+    #[test]
+    fn percentile_uses_the_nearest_rank() {
+        let values: Vec<u32> = (1..=20).collect();
+        assert_eq!(percentile(&values, 50), Some(10));
+        assert_eq!(percentile(&values, 95), Some(19));
+        assert_eq!(percentile(&values, 100), Some(20));
+        assert_eq!(percentile(&values, 0), Some(1));
+        assert_eq!(percentile(&[42], 50), Some(42));
+        assert_eq!(percentile(&[42], 95), Some(42));
+        assert_eq!(percentile(&[10, 20, 30], 50), Some(20));
+        assert_eq!(percentile(&[], 50), None);
+    }
+
+    #[test]
+    fn prune_drops_old_samples_and_empty_domains() {
+        let recent = vec![sample(29), sample(0)];
+        let mut history = BTreeMap::from([
+            (
+                "kept.example".to_string(),
+                [vec![sample(45)], recent.clone()].concat(),
+            ),
+            ("gone.example".to_string(), vec![sample(31), sample(60)]),
+        ]);
+        prune(&mut history);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history["kept.example"], recent);
+    }
+
+    #[test]
+    fn prune_keeps_the_newest_samples_past_the_cap() {
+        let mut samples: Vec<Sample> = (0..MAX_SAMPLES + 5)
+            .map(|i| Sample {
+                at: now() - DAY_SECS + i as u64,
+                ok: true,
+                latency_ms: None,
+            })
+            .collect();
+        let newest = samples.last().cloned();
+        let mut history = BTreeMap::from([("busy.example".to_string(), samples.clone())]);
+        prune(&mut history);
+        samples.drain(..5);
+        assert_eq!(history["busy.example"], samples);
+        assert_eq!(history["busy.example"].last().cloned(), newest);
+    }
+}
@@ -0,0 +1,122 @@
+//! Servers watched for sign-up changes.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::evaluate;
+use crate::probe::{self, ProbeClient};
+use crate::store::{self, now};
+
+const FORMAT_VERSION: u32 = 1;
+const HOST_BUDGET_SECS: u64 = 30;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchItem {
+    pub domain: String,
+    pub added_at: u64,
+    /// Last known registration mode: "open" | "approval" | "closed".
+    pub signups: Option<String>,
+    pub checked_at: Option<u64>,
+}
+
+/// A registration mode change found by [`WatchlistStore::check_all`].
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct WatchChange {
+    pub domain: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Domains whose registrations the user wants to hear about.
+pub struct WatchlistStore {
+    path: PathBuf,
+}
+
+impl WatchlistStore {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    pub fn list(&self) -> Result<Vec<WatchItem>, Error> {
+        let _guard = store::lock()?;
+        self.load()
+    }
+
+    pub fn add(&self, domain: &str) -> Result<Vec<WatchItem>, Error> {
+        let domain = probe::normalize_domain(domain)?;
+        let _guard = store::lock()?;
+        let mut items = self.load()?;
+        if !items.iter().any(|i| i.domain == domain) {
+            items.push(WatchItem {
+                domain,
+                added_at: now(),
+                signups: None,
+                checked_at: None,
+            });
+            self.save(&items)?;
+        }
+        Ok(items)
+    }
+
+    pub fn remove(&self, domain: &str) -> Result<Vec<WatchItem>, Error> {
+        let domain = probe::normalize_domain(domain)?;
+        let _guard = store::lock()?;
+        let mut items = self.load()?;
+        items.retain(|i| i.domain != domain);
+        self.save(&items)?;
+        Ok(items)
+    }
+
+    /// Recheck every watched domain and save the new modes. Unreachable
+    /// hosts keep their last known mode; the first check of a domain only
+    /// sets it, it is not a change.
+    pub fn check_all(&self) -> Result<Vec<WatchChange>, Error> {
+        let domains: Vec<String> = self.list()?.into_iter().map(|i| i.domain).collect();
+        let results = probe::parallel_map(&domains, 4, |domain| {
+            ProbeClient::with_deadline(domain, Duration::from_secs(HOST_BUDGET_SECS))
+                .and_then(|client| evaluate::registration_status(&client))
+                .ok()
+        });
+
+        // Reload: the list may have changed while hosts were probed
+        let _guard = store::lock()?;
+        let mut items = self.load()?;
+        let mut changes = Vec::new();
+        for (domain, status) in domains.iter().zip(results) {
+            let (Some(status), Some(item)) =
+                (status, items.iter_mut().find(|i| &i.domain == domain))
+            else {
+                continue;
+            };
+            if let Some(previous) = item.signups.as_deref().filter(|p| *p != status) {
+                changes.push(WatchChange {
+                    domain: domain.clone(),
+                    from: previous.to_string(),
+                    to: status.to_string(),
+                });
+            }
+            item.signups = Some(status.to_string());
+            item.checked_at = Some(now());
+        }
+        self.save(&items)?;
+        Ok(changes)
+    }
+
+    // Refuse a corrupt file or one written by a newer version rather than
+    // overwrite it
+    fn load(&self) -> Result<Vec<WatchItem>, Error> {
+        store::read(&self.path, FORMAT_VERSION)
+    }
+
+    fn save(&self, items: &[WatchItem]) -> Result<(), Error> {
+        store::write(&self.path, FORMAT_VERSION, &items)
+    }
+}
@@ -0,0 +1,107 @@
+use instances_finder_core::advisories::AdvisoryList;
+
+// One advisory fixed on the 4.1 and 4.2 branches
+fn list() -> AdvisoryList {
+    AdvisoryList::parse(
+        br#"{
+            "updated": "2024-02-01",
+            "advisories": [{
+                "id": "CVE-TEST-1",
+                "title": "Test",
+                "severity": "high",
+                "published": "2024-02-01",
+                "fixed": ["4.1.13", "4.2.5"]
+            }]
+        }"#,
+    )
+    .unwrap()
+}
+
+fn vulnerable(version: &str) -> bool {
+    list().check("mastodon", version).is_some()
+}
+
+#[test]
+fn range_boundaries_follow_the_branch_fix() {
+    assert!(vulnerable("4.2.4"));
+    assert!(!vulnerable("4.2.5"));
+    assert!(!vulnerable("4.2.6"));
+    assert!(vulnerable("4.1.12"));
+    assert!(!vulnerable("4.1.13"));
+    // A newer branch than any fix was never affected
+    assert!(!vulnerable("4.3.0"));
+    // An older branch without a fix of its own is end of life
+    assert!(vulnerable("4.0.99"));
+    assert!(vulnerable("3.5.17"));
+}
+
+#[test]
+fn pre_releases_come_before_their_release() {
+    assert!(vulnerable("4.2.5-beta.1"));
+    assert!(vulnerable("4.2.5-rc.2"));
+    assert!(!vulnerable("4.2.6-beta.1"));
+    assert!(!vulnerable("4.3.0-alpha.1"));
+}
+
+#[test]
+fn build_metadata_and_prefixes_are_ignored() {
+    assert!(vulnerable("4.2.4+glitch"));
+    assert!(!vulnerable("4.2.5+glitch"));
+    assert!(!vulnerable("v4.2.5+hometown-1.1.1"));
+    assert!(vulnerable(" v4.2.4 "));
+    assert!(list().check("glitch-soc", "4.2.4+glitch").is_some());
+}
+
+#[test]
+fn skips_unreadable_versions_and_other_software() {
+    assert!(!vulnerable("nightly"));
+    assert!(!vulnerable(""));
+    assert!(list().check("pleroma", "2.0.0").is_none());
+}
+
+#[test]
+fn reports_the_highest_severity() {
+    let report = list().check("mastodon", "4.2.4").unwrap();
+    assert_eq!(report.severity, "high");
+    assert_eq!(report.version, "4.2.4");
+    assert_eq!(report.critical_unpatched_days, None);
+}
+
+#[test]
+fn rejects_invalid_lists() {
+    let bad_version = br#"{"updated": "2024-02-01", "advisories": [
+        {"id": "X", "title": "", "severity": "low", "published": "2024-01-01", "fixed": ["4.x"]}
+    ]}"#;
+    assert!(AdvisoryList::parse(bad_version).is_err());
+    let bad_date = br#"{"updated": "2024-13-01", "advisories": []}"#;
+    assert!(AdvisoryList::parse(bad_date).is_err());
+}
+
+// Fails once the bundled list has not been refreshed for two years
+#[test]
+fn bundled_list_is_maintained() {
+    let bundled = AdvisoryList::bundled();
+    let age = bundled.age_days().expect("bundled list has a valid date");
+    assert!(
+        age <= 730,
+        "data/advisories.json was last updated {age} days ago ({})",
+        bundled.updated
+    );
+}
+
+#[test]
+fn load_compares_dates_not_text() {
+    let bundled = AdvisoryList::bundled();
+    let year: u32 = bundled.updated[..4].parse().unwrap();
+    assert!(!bundled.updated.ends_with("-01-01"));
+    let path = std::env::temp_dir().join(format!("advisories-{}.json", std::process::id()));
+    let list = |updated: String| format!(r#"{{"updated": "{updated}", "advisories": []}}"#);
+
+    // "YYYY-1-1" sorts after "YYYY-03-13" as text but is older
+    std::fs::write(&path, list(format!("{year}-1-1"))).unwrap();
+    assert_eq!(AdvisoryList::load(&path), bundled);
+
+    std::fs::write(&path, list(format!("{}-1-1", year + 1))).unwrap();
+    assert!(AdvisoryList::load(&path).advisories.is_empty());
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,49 @@
+use instances_finder_core::batch::parse_domain_list;
+
+fn parse(text: &str) -> (Vec<String>, Vec<String>) {
+    let (domains, errors) = parse_domain_list(text);
+    (domains, errors.into_iter().map(|e| e.domain).collect())
+}
+
+#[test]
+fn skips_the_header_after_comments_and_blank_lines() {
+    let (domains, errors) = parse("# shortlist\n\ndomain,users\nmastodon.social,100\n");
+    assert_eq!(domains, ["mastodon.social"]);
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn reports_rows_without_a_domain_after_the_first() {
+    let (domains, errors) = parse("mastodon.social\nnot a domain\nfosstodon.org\n");
+    assert_eq!(domains, ["mastodon.social", "fosstodon.org"]);
+    assert_eq!(errors, ["not a domain"]);
+
+    let (_, errors) = parse("domain\nname\n");
+    assert_eq!(errors, ["name"]);
+}
+
+#[test]
+fn ignores_comments_and_a_byte_order_mark() {
+    let (domains, errors) = parse("\u{feff}domain\n# mastodon.social\n  # note\nfosstodon.org\n");
+    assert_eq!(domains, ["fosstodon.org"]);
+    assert!(errors.is_empty());
+
+    let (domains, _) = parse("\u{feff}mastodon.social\r\nfosstodon.org\r\n");
+    assert_eq!(domains, ["mastodon.social", "fosstodon.org"]);
+}
+
+#[test]
+fn takes_every_domain_of_a_pasted_line() {
+    let (domains, errors) = parse("mastodon.social fosstodon.org, https://Mastodon.social/about");
+    assert_eq!(domains, ["mastodon.social", "fosstodon.org"]);
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn takes_the_first_domain_of_a_csv_row() {
+    let (domains, errors) = parse(
+        "name;domain;mirror\n\"Fosstodon\";fosstodon.org;backup.example\nMastodon Social,mastodon.social,fr\n",
+    );
+    assert_eq!(domains, ["fosstodon.org", "mastodon.social"]);
+    assert!(errors.is_empty());
+}
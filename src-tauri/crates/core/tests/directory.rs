@@ -0,0 +1,310 @@
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use instances_finder_core::advisories::AdvisoryList;
+use instances_finder_core::directory;
+use instances_finder_core::error::Error;
+use instances_finder_core::model::FetchParams;
+use instances_finder_mock_api::{canned_instances, Failure, MockDirectory, DEFAULT_TOKEN};
+
+// The base URL is process-wide: run one mock at a time
+static SERIAL: Mutex<()> = Mutex::new(());
+
+fn mock() -> (MutexGuard<'static, ()>, MockDirectory) {
+    let guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let mock = MockDirectory::start().expect("mock server starts");
+    directory::set_api_base_url(Some(&mock.base_url()));
+    (guard, mock)
+}
+
+fn search(params: &FetchParams) -> Result<Vec<String>, Error> {
+    directory::search(DEFAULT_TOKEN, params, &AdvisoryList::bundled())
+        .map(|items| items.into_iter().map(|i| i.domain).collect())
+}
+
+fn rejected_with(err: &Error, code: &str) -> bool {
+    matches!(err, Error::DirectoryStatus { status, .. } if status.starts_with(code))
+}
+
+#[test]
+fn lists_open_servers_that_are_up_by_default() {
+    let (_guard, mock) = mock();
+    let domains = search(&FetchParams::default()).unwrap();
+    assert_eq!(domains.len(), 8);
+    assert!(!domains.contains(&"down.example.eu".to_string()));
+    assert!(!domains.contains(&"tooter.example.de".to_string()));
+
+    let request = &mock.requests()[0];
+    assert_eq!(request.path, "/api/1.0/instances/list");
+    assert_eq!(request.param("count"), Some("200"));
+    assert_eq!(
+        request.authorization.as_deref(),
+        Some(format!("Bearer {DEFAULT_TOKEN}").as_str())
+    );
+}
+
+#[test]
+fn maps_directory_entries() {
+    let (_guard, _mock) = mock();
+    let params = FetchParams {
+        include_closed: Some(true),
+        include_down: Some(true),
+        ..FetchParams::default()
+    };
+    let items = directory::search(DEFAULT_TOKEN, &params, &AdvisoryList::bundled()).unwrap();
+    assert_eq!(items.len(), 12);
+
+    let tooter = items
+        .iter()
+        .find(|i| i.domain == "tooter.example.de")
+        .unwrap();
+    assert_eq!(tooter.signups, "approval");
+    assert_eq!(tooter.size, 1);
+    assert_eq!(tooter.region, "eu");
+    assert_eq!(tooter.users, Some(450));
+    assert_eq!(tooter.languages, ["de"]);
+    // 4.1.2 predates several critical fixes
+    assert_eq!(
+        tooter.vulnerability.as_ref().map(|v| v.severity.as_str()),
+        Some("critical")
+    );
+
+    let social = items
+        .iter()
+        .find(|i| i.domain == "social.example.us")
+        .unwrap();
+    assert_eq!((social.size, social.region.as_str()), (3, "na"));
+    assert!(social.vulnerability.is_none());
+
+    let down = items
+        .iter()
+        .find(|i| i.domain == "down.example.eu")
+        .unwrap();
+    assert!(down.availability < 0.5);
+
+    let bare = items
+        .iter()
+        .find(|i| i.domain == "nolang.example.org")
+        .unwrap();
+    assert_eq!((bare.users, bare.size), (None, 1));
+    assert!(bare.languages.is_empty() && bare.description.is_empty());
+}
+
+#[test]
+fn applies_client_side_filters() {
+    let (_guard, mock) = mock();
+    let french = FetchParams {
+        language: Some("fr".into()),
+        ..FetchParams::default()
+    };
+    let mut domains = search(&french).unwrap();
+    domains.sort();
+    assert_eq!(
+        domains,
+        [
+            "gros.example.fr",
+            "libre.example.be",
+            "mastodon.example.fr",
+            "piaille.example.fr"
+        ]
+    );
+    assert_eq!(mock.requests()[0].param("language"), Some("fr"));
+
+    let french_or_irish = FetchParams {
+        language: Some("fr,ga".into()),
+        ..FetchParams::default()
+    };
+    let before = mock.requests().len();
+    let domains = search(&french_or_irish).unwrap();
+    assert_eq!(domains.len(), 5);
+    let asked: Vec<_> = mock.requests()[before..]
+        .iter()
+        .filter_map(|r| r.param("language").map(str::to_string))
+        .collect();
+    assert_eq!(asked, ["fr", "ga"]);
+
+    let small_eu = FetchParams {
+        region: Some("eu".into()),
+        size: Some("small".into()),
+        ..FetchParams::default()
+    };
+    let mut domains = search(&small_eu).unwrap();
+    domains.sort();
+    assert_eq!(domains, ["libre.example.be", "mastodon.example.fr"]);
+
+    let approval = FetchParams {
+        include_closed: Some(true),
+        signups: Some("approval".into()),
+        ..FetchParams::default()
+    };
+    let mut domains = search(&approval).unwrap();
+    domains.sort();
+    assert_eq!(
+        domains,
+        ["closed.example.it", "tech.example.ca", "tooter.example.de"]
+    );
+
+    let patched = FetchParams {
+        hide_vulnerable: Some(true),
+        ..FetchParams::default()
+    };
+    assert!(!search(&patched)
+        .unwrap()
+        .contains(&"libre.example.be".to_string()));
+}
+
+#[test]
+fn follows_pagination_up_to_max() {
+    let (_guard, mock) = mock();
+    mock.set_page_size(4);
+    let params = FetchParams {
+        include_closed: Some(true),
+        include_down: Some(true),
+        max: Some(10),
+        ..FetchParams::default()
+    };
+    let domains = search(&params).unwrap();
+    assert_eq!(domains.len(), 10);
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].param("min_id"), None);
+    assert_eq!(requests[1].param("min_id"), Some("4"));
+    assert_eq!(requests[2].param("min_id"), Some("8"));
+    assert_eq!(requests[2].param("count"), Some("2"));
+}
+
+#[test]
+fn stops_on_the_last_page() {
+    let (_guard, mock) = mock();
+    mock.set_page_size(5);
+    let domains = search(&FetchParams::default()).unwrap();
+    assert_eq!(domains.len(), 8);
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn reports_rejected_tokens() {
+    let (_guard, mock) = mock();
+    mock.set_token("another-token");
+    let err = search(&FetchParams::default()).unwrap_err();
+    assert!(rejected_with(&err, "401"), "{err}");
+    assert!(directory::test_token(DEFAULT_TOKEN).is_err());
+    assert!(directory::test_token("another-token").is_ok());
+    assert_eq!(mock.requests()[2].path, "/api/1.0/instances/sample");
+}
+
+#[test]
+fn reports_server_errors() {
+    let (_guard, mock) = mock();
+    mock.set_failure(Some(Failure::ServerError));
+    let err = search(&FetchParams::default()).unwrap_err();
+    assert!(rejected_with(&err, "500"), "{err}");
+
+    mock.set_failure(Some(Failure::Unauthorized));
+    let err = directory::test_token(DEFAULT_TOKEN).unwrap_err();
+    assert!(rejected_with(&err, "401"), "{err}");
+}
+
+#[test]
+fn waits_once_for_short_rate_limits() {
+    let (_guard, mock) = mock();
+    mock.queue_failure(Failure::RateLimited { retry_after: 1 });
+    let started = Instant::now();
+    assert_eq!(search(&FetchParams::default()).unwrap().len(), 8);
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn gives_up_on_long_or_repeated_rate_limits() {
+    let (_guard, mock) = mock();
+    mock.queue_failure(Failure::RateLimited { retry_after: 3600 });
+    let err = search(&FetchParams::default()).unwrap_err();
+    assert_eq!(err, Error::RateLimitedRetry { secs: 3600 });
+    assert_eq!(err.params(), [("secs", "3600".to_string())]);
+    assert_eq!(mock.requests().len(), 1);
+
+    mock.set_failure(Some(Failure::RateLimited { retry_after: 0 }));
+    let err = search(&FetchParams::default()).unwrap_err();
+    assert_eq!(err.code(), "rate_limited_retry", "{err}");
+    assert_eq!(mock.requests().len(), 3);
+}
+
+#[test]
+fn tolerates_slow_responses() {
+    let (_guard, mock) = mock();
+    mock.set_delay(Duration::from_millis(300));
+    assert_eq!(search(&FetchParams::default()).unwrap().len(), 8);
+}
+
+#[test]
+fn keeps_names_that_do_not_normalize() {
+    let (_guard, mock) = mock();
+    let mut instances = canned_instances();
+    instances.truncate(1);
+    let mut odd = instances[0].clone();
+    odd["name"] = "Under_Score.Example.FR".into();
+    instances.push(odd);
+    let mut idn = instances[0].clone();
+    idn["name"] = "Café.Example.FR".into();
+    instances.push(idn);
+    mock.set_instances(instances);
+
+    let domains = search(&FetchParams::default()).unwrap();
+    assert_eq!(
+        domains,
+        [
+            "mastodon.example.fr",
+            "under_score.example.fr",
+            "xn--caf-dma.example.fr"
+        ]
+    );
+}
+
+#[test]
+fn enrichment_keeps_hosts_that_cannot_be_probed() {
+    let (_guard, mock) = mock();
+    let mut odd = canned_instances()[0].clone();
+    odd["name"] = "Under_Score.Example.FR".into();
+    mock.set_instances(vec![odd]);
+
+    let params = FetchParams {
+        check_notices: Some(true),
+        check_activity: Some(true),
+        ..FetchParams::default()
+    };
+    assert_eq!(search(&params).unwrap(), ["under_score.example.fr"]);
+}
+
+#[test]
+fn collects_languages() {
+    let (_guard, mock) = mock();
+    let languages = directory::languages(DEFAULT_TOKEN).unwrap();
+    // Closed servers are not listed by default
+    assert_eq!(languages, ["en", "fr", "ga", "nl"]);
+    assert_eq!(mock.requests()[0].param("count"), Some("500"));
+}
+
+#[test]
+fn caches_by_params() {
+    let (_guard, _mock) = mock();
+    let path = std::env::temp_dir().join(format!(
+        "instances-finder-cache-test-{}.json",
+        std::process::id()
+    ));
+    let params = FetchParams {
+        language: Some("fr".into()),
+        ..FetchParams::default()
+    };
+    let items = directory::search(DEFAULT_TOKEN, &params, &AdvisoryList::bundled()).unwrap();
+    directory::write_cache(&path, &params, &items);
+
+    let cached = directory::read_cache(&path, &params).unwrap();
+    assert_eq!(cached.len(), items.len());
+    assert!(directory::read_cache(&path, &FetchParams::default()).is_none());
+    assert_eq!(directory::cache_info(&path).unwrap().items, items.len());
+
+    directory::clear_cache(&path).unwrap();
+    assert!(directory::cache_info(&path).is_none());
+}
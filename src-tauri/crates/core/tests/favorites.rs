@@ -0,0 +1,54 @@
+use instances_finder_core::favorites::{self, FavoritesStore};
+use instances_finder_core::model::JsInstance;
+
+fn instance(description: &str, size_label: &str, probed: bool) -> JsInstance {
+    serde_json::from_value(serde_json::json!({
+        "domain": "mastodon.social",
+        "description": description,
+        "languages": ["en"],
+        "signups": "open",
+        "size": 3,
+        "sizeLabel": size_label,
+        "region": "eu",
+        "availability": 0.999,
+        "probed": probed,
+    }))
+    .unwrap()
+}
+
+fn fields(before: &JsInstance, after: &JsInstance) -> Vec<String> {
+    favorites::diff(before, after)
+        .into_iter()
+        .map(|c| c.field)
+        .collect()
+}
+
+#[test]
+fn compares_directory_text_only_with_the_directory() {
+    let listed = instance("From instances.social", "large", false);
+    let probed = instance("From the server", "large", true);
+    assert!(fields(&listed, &probed).is_empty());
+    assert_eq!(
+        fields(&probed, &instance("Rewritten", "large", true)),
+        ["description"]
+    );
+    assert_eq!(
+        fields(&listed, &instance("From the server", "medium", true)),
+        ["sizeLabel"]
+    );
+}
+
+#[test]
+fn normalizes_domains_like_add() {
+    let path = std::env::temp_dir().join(format!("favorites-{}.json", std::process::id()));
+    let store = FavoritesStore::new(&path);
+    store
+        .add(instance("", "large", false), Some("note".into()))
+        .unwrap();
+    let items = store
+        .set_note("https://Mastodon.Social/", "changed")
+        .unwrap();
+    assert_eq!(items[0].note, "changed");
+    assert!(store.remove("Mastodon.Social").unwrap().is_empty());
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,109 @@
+use instances_finder_core::link::{self, DeepLink};
+use instances_finder_core::model::FetchParams;
+
+fn search(link: &str) -> (FetchParams, Vec<String>, String) {
+    match link::parse(link).unwrap() {
+        DeepLink::Search {
+            params,
+            preferences,
+        } => (params, preferences.languages, preferences.size),
+        other => panic!("expected a search, got {other:?}"),
+    }
+}
+
+#[test]
+fn parses_search_links() {
+    let (params, languages, size) =
+        search("instances-finder://search?lang=fr&size=small&signups=open");
+    assert_eq!(languages, ["fr"]);
+    assert_eq!(size, "small");
+    assert_eq!(params.language.as_deref(), Some("fr"));
+    assert_eq!(params.size.as_deref(), Some("small"));
+    assert_eq!(params.signups.as_deref(), Some("open"));
+    assert_eq!(params.region, None);
+
+    let (params, languages, size) = search("instances-finder://search/?lang=fr,en");
+    assert_eq!(languages, ["fr", "en"]);
+    assert_eq!(params.language.as_deref(), Some("fr,en"));
+    assert_eq!(size, "any");
+
+    let (params, _, _) = search("instances-finder://search?privacy=strict&vulnerable=hide");
+    assert_eq!(params.exclude_cdn, Some(true));
+    assert_eq!(params.exclude_third_party, Some(true));
+    assert_eq!(params.hide_vulnerable, Some(true));
+}
+
+#[test]
+fn parses_instance_links() {
+    assert_eq!(
+        link::parse("instances-finder://instance/Mastodon.Social").unwrap(),
+        DeepLink::Instance {
+            domain: "mastodon.social".into()
+        }
+    );
+}
+
+#[test]
+fn rejects_anything_unexpected() {
+    for bad in [
+        "https://search?lang=fr",
+        "instances-finder://settings",
+        "instances-finder://search?lang=fr&lang=en",
+        "instances-finder://search?colour=blue",
+        "instances-finder://search?size=huge",
+        "instances-finder://search?lang=French",
+        "instances-finder://search?lang=",
+        "instances-finder://search?software=<script>",
+        "instances-finder://search/extra?lang=fr",
+        "instances-finder://search?lang=fr#top",
+        "instances-finder://user@search?lang=fr",
+        "instances-finder://instance/",
+        "instances-finder://instance/mastodon.social/about",
+        "instances-finder://instance/mastodon.social?x=1",
+        "instances-finder://instance/https%3A%2F%2Fmastodon.social",
+        "instances-finder://instance/127.0.0.1",
+        "instances-finder://instance/localhost",
+    ] {
+        assert!(link::parse(bad).is_err(), "{bad} should be rejected");
+    }
+    let long = format!("instances-finder://search?lang=fr{}", "&".repeat(3000));
+    assert!(link::parse(&long).is_err());
+}
+
+#[test]
+fn serializes_for_the_webview() {
+    let value = serde_json::to_value(link::parse("instances-finder://instance/a.example").unwrap())
+        .unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({ "kind": "instance", "domain": "a.example" })
+    );
+    let value =
+        serde_json::to_value(link::parse("instances-finder://search?sort=peers").unwrap()).unwrap();
+    assert_eq!(value["kind"], "search");
+    assert_eq!(value["preferences"]["sort"], "peers");
+}
+
+#[test]
+fn search_links_round_trip() {
+    let params = FetchParams {
+        language: Some("fr".into()),
+        size: Some("small".into()),
+        min_security: Some("B".into()),
+        exclude_cdn: Some(true),
+        exclude_third_party: Some(false),
+        hide_vulnerable: Some(true),
+        ..FetchParams::default()
+    };
+    let link = link::search_link(&params);
+    assert_eq!(
+        link,
+        "instances-finder://search?lang=fr&size=small&security=B&privacy=no_cdn&vulnerable=hide"
+    );
+    let (parsed, _, _) = search(&link);
+    assert_eq!(parsed, params);
+
+    let all = link::search_link(&FetchParams::default());
+    assert_eq!(all, "instances-finder://search");
+    assert!(link::parse(&all).is_ok());
+}
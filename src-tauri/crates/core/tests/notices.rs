@@ -0,0 +1,66 @@
+use instances_finder_core::notices::classify;
+
+fn kind(text: &str) -> Option<&'static str> {
+    classify(text).map(|(kind, _)| kind)
+}
+
+#[test]
+fn finds_shutdowns_of_the_server() {
+    for text in [
+        "This instance will shut down on March 1st.",
+        "Sadly, we are shutting down this server at the end of the month.",
+        "The server will be closed on June 30.",
+        "Our instance is going offline for good.",
+        "Cette instance fermera le 1er mars.",
+        "Fermeture de l\u{2019}instance prévue fin juin.",
+        "Le serveur cessera de fonctionner en décembre.",
+    ] {
+        assert_eq!(kind(text), Some("shutdown"), "{text}");
+    }
+}
+
+#[test]
+fn ignores_registration_closures() {
+    for text in [
+        "Registrations will be closed during the holidays.",
+        "Sign-ups are closing on Friday.",
+        "This instance is closing registrations for now.",
+        "The server will close sign-ups while we catch up on reports.",
+        "We are no longer accepting new accounts.",
+        "Les inscriptions seront fermées cet été.",
+        "L'instance fermera les inscriptions pendant les vacances.",
+        "Cette instance n'accepte plus de nouvelles inscriptions.",
+        "Registrations are permanently closed.",
+        "End of life of the old theme is planned.",
+    ] {
+        assert_eq!(kind(text), None, "{text}");
+    }
+}
+
+#[test]
+fn ignores_negated_shutdowns() {
+    assert_eq!(kind("We are not shutting down this instance!"), None);
+    assert_eq!(kind("Rumors aside, we won't shut down this server."), None);
+}
+
+#[test]
+fn finds_migrations_and_read_only_mode() {
+    assert_eq!(
+        kind("We are moving to a new domain: social.example."),
+        Some("migration")
+    );
+    assert_eq!(
+        kind("This server is now read-only; please export your data."),
+        Some("read_only")
+    );
+    assert_eq!(
+        kind("L'instance passe en lecture seule demain."),
+        Some("read_only")
+    );
+}
+
+#[test]
+fn excerpt_is_the_matching_sentence() {
+    let (_, excerpt) = classify("Welcome! This instance will shut down in May. Thanks.").unwrap();
+    assert_eq!(excerpt, "This instance will shut down in May.");
+}
@@ -0,0 +1,74 @@
+use instances_finder_core::error::Error;
+use instances_finder_core::probe::normalize_domain;
+
+fn normalized(input: &str) -> String {
+    normalize_domain(input).unwrap()
+}
+
+#[test]
+fn strips_scheme_path_and_port() {
+    assert_eq!(normalized("mastodon.social"), "mastodon.social");
+    assert_eq!(normalized("  Mastodon.Social \n"), "mastodon.social");
+    assert_eq!(normalized("https://mastodon.social"), "mastodon.social");
+    assert_eq!(
+        normalized("HTTP://mastodon.social/about"),
+        "mastodon.social"
+    );
+    assert_eq!(
+        normalized("https://mastodon.social:443/@alice"),
+        "mastodon.social"
+    );
+    assert_eq!(normalized("mastodon.social?lang=fr#top"), "mastodon.social");
+}
+
+#[test]
+fn keeps_the_host_of_an_account_address() {
+    assert_eq!(normalized("alice@mastodon.social"), "mastodon.social");
+    assert_eq!(normalized("@alice@mastodon.social"), "mastodon.social");
+    assert_eq!(
+        normalized("https://alice@mastodon.social/"),
+        "mastodon.social"
+    );
+}
+
+#[test]
+fn drops_the_trailing_dot() {
+    assert_eq!(normalized("mastodon.social."), "mastodon.social");
+    assert!(normalize_domain("mastodon.social..").is_err());
+}
+
+#[test]
+fn converts_internationalized_names() {
+    assert_eq!(normalized("café.example"), "xn--caf-dma.example");
+    assert_eq!(
+        normalized("https://CAFÉ.example/about"),
+        "xn--caf-dma.example"
+    );
+    assert_eq!(normalized("xn--caf-dma.example"), "xn--caf-dma.example");
+}
+
+#[test]
+fn rejects_addresses_and_invalid_names() {
+    for bad in [
+        "",
+        "   ",
+        "https://",
+        "localhost",
+        "192.168.0.1",
+        "https://10.0.0.1:8080/",
+        "[::1]",
+        "https://[2001:db8::1]/",
+        "-bad.example",
+        "bad-.example",
+        "under_score.example",
+        "two..dots.example",
+        "spa ce.example",
+    ] {
+        assert!(
+            matches!(normalize_domain(bad), Err(Error::InvalidDomain { .. })),
+            "{bad:?} was accepted"
+        );
+    }
+    let long = format!("{}.example", "a".repeat(64));
+    assert!(normalize_domain(&long).is_err());
+}
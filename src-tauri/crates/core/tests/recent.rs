@@ -0,0 +1,18 @@
+use instances_finder_core::model::FetchParams;
+use instances_finder_core::recent::RecentStore;
+
+#[test]
+fn refuses_to_overwrite_unreadable_files() {
+    for (name, contents) in [
+        ("newer", r#"{"version": 99, "items": []}"#),
+        ("corrupt", "{not json"),
+    ] {
+        let path = std::env::temp_dir().join(format!("recent-{name}-{}.json", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        let store = RecentStore::new(&path);
+        assert!(store.list().is_err());
+        assert!(store.record(&FetchParams::default()).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+        let _ = std::fs::remove_file(path);
+    }
+}
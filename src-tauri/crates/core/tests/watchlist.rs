@@ -0,0 +1,33 @@
+use instances_finder_core::watchlist::WatchlistStore;
+
+fn temp_file(name: &str, contents: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("watchlist-{name}-{}.json", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn refuses_to_overwrite_unreadable_files() {
+    for (name, contents) in [
+        ("newer", r#"{"version": 99, "items": []}"#),
+        ("corrupt", "{not json"),
+    ] {
+        let path = temp_file(name, contents);
+        let store = WatchlistStore::new(&path);
+        assert!(store.list().is_err());
+        assert!(store.add("mastodon.social").is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn starts_empty_without_a_file() {
+    let path = std::env::temp_dir().join(format!("watchlist-missing-{}.json", std::process::id()));
+    let store = WatchlistStore::new(&path);
+    assert!(store.list().unwrap().is_empty());
+    let items = store.add("https://Mastodon.Social/").unwrap();
+    assert_eq!(items[0].domain, "mastodon.social");
+    assert!(store.remove("mastodon.social").unwrap().is_empty());
+    let _ = std::fs::remove_file(path);
+}
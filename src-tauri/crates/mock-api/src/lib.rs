@@ -0,0 +1,326 @@
+//! In-process fake of the instances.social API (`instances/list` and
+//! `instances/sample`) for tests and `xtask mock-api`.
+//!
+//! ```no_run
+//! use instances_finder_mock_api::{Failure, MockDirectory};
+//!
+//! let mock = MockDirectory::start().unwrap();
+//! mock.set_page_size(5);
+//! mock.queue_failure(Failure::RateLimited { retry_after: 1 });
+//! // point the client at `mock.base_url()` and run the code under test
+//! assert_eq!(mock.requests().len(), 0);
+//! ```
+
+use std::{
+    collections::VecDeque,
+    io,
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Request, Response, Server};
+
+const CANNED: &str = include_str!("../data/instances.json");
+
+/// Token accepted unless `set_token` changes it.
+pub const DEFAULT_TOKEN: &str = "test-token";
+
+/// Error answered instead of the canned data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Failure {
+    /// 401, as for a revoked token.
+    Unauthorized,
+    /// 429 with `Retry-After: <retry_after>`.
+    RateLimited { retry_after: u64 },
+    /// 500.
+    ServerError,
+}
+
+/// A request the server received, for assertions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub authorization: Option<String>,
+}
+
+impl RecordedRequest {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+struct State {
+    instances: Vec<Value>,
+    token: String,
+    page_size: usize,
+    delay: Duration,
+    failure: Option<Failure>,
+    queued: VecDeque<Failure>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// Fake directory listening on localhost until dropped.
+pub struct MockDirectory {
+    server: Arc<Server>,
+    state: Arc<Mutex<State>>,
+    port: u16,
+    worker: Option<JoinHandle<()>>,
+}
+
+/// The bundled sample directory: a dozen servers mixing languages, sizes,
+/// regions, open and closed sign-ups, one down and some outdated versions.
+pub fn canned_instances() -> Vec<Value> {
+    serde_json::from_str(CANNED).expect("canned instances.json is valid")
+}
+
+impl MockDirectory {
+    /// Listen on a free port of 127.0.0.1.
+    pub fn start() -> io::Result<Self> {
+        Self::start_on(0)
+    }
+
+    /// Listen on `port` of 127.0.0.1 (0 picks a free one).
+    pub fn start_on(port: u16) -> io::Result<Self> {
+        let server = Server::http(("127.0.0.1", port)).map_err(io::Error::other)?;
+        let port = server
+            .server_addr()
+            .to_ip()
+            .map(|a| a.port())
+            .ok_or_else(|| io::Error::other("not an IP listener"))?;
+        let server = Arc::new(server);
+        let state = Arc::new(Mutex::new(State {
+            instances: canned_instances(),
+            token: DEFAULT_TOKEN.into(),
+            page_size: 100,
+            delay: Duration::ZERO,
+            failure: None,
+            queued: VecDeque::new(),
+            requests: Vec::new(),
+        }));
+        let worker = {
+            let server = Arc::clone(&server);
+            let state = Arc::clone(&state);
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handle(&state, request);
+                }
+            })
+        };
+        Ok(Self {
+            server,
+            state,
+            port,
+            worker: Some(worker),
+        })
+    }
+
+    /// API root to give the client, like `https://instances.social/api/1.0`.
+    pub fn base_url(&self) -> String {
+        format!("http://127.0.0.1:{}/api/1.0", self.port)
+    }
+
+    pub fn set_instances(&self, instances: Vec<Value>) {
+        self.state.lock().unwrap().instances = instances;
+    }
+
+    /// Bearer token to accept; anything else gets a 401.
+    pub fn set_token(&self, token: &str) {
+        self.state.lock().unwrap().token = token.into();
+    }
+
+    /// Most instances per `instances/list` page, whatever `count` asks.
+    pub fn set_page_size(&self, size: usize) {
+        self.state.lock().unwrap().page_size = size.max(1);
+    }
+
+    /// Wait this long before answering each request.
+    pub fn set_delay(&self, delay: Duration) {
+        self.state.lock().unwrap().delay = delay;
+    }
+
+    /// Answer every request with `failure` until set back to `None`.
+    pub fn set_failure(&self, failure: Option<Failure>) {
+        self.state.lock().unwrap().failure = failure;
+    }
+
+    /// Answer the next request with `failure`, then recover.
+    pub fn queue_failure(&self, failure: Failure) {
+        self.state.lock().unwrap().queued.push_back(failure);
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockDirectory {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn parse_query(url: &str) -> (String, Vec<(String, String)>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let pairs = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (k, v) = p.split_once('=').unwrap_or((p, ""));
+            (decode(k), decode(v))
+        })
+        .collect();
+    (path.to_string(), pairs)
+}
+
+// Enough percent-decoding for the values the client sends
+fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(b) => {
+                        out.push(b);
+                        i += 2;
+                    }
+                    Err(_) => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn json_response(status: u16, body: &Value) -> Response<io::Cursor<Vec<u8>>> {
+    let header = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+    Response::from_data(body.to_string().into_bytes())
+        .with_status_code(status)
+        .with_header(header)
+}
+
+fn handle(state: &Mutex<State>, request: Request) {
+    let (path, query) = parse_query(request.url());
+    let authorization = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .map(|h| h.value.to_string());
+    let recorded = RecordedRequest {
+        path: path.clone(),
+        query,
+        authorization,
+    };
+    let (delay, response) = {
+        let mut st = state.lock().unwrap();
+        st.requests.push(recorded.clone());
+        let failure = st.queued.pop_front().or(st.failure);
+        (st.delay, respond(&st, &recorded, failure))
+    };
+    if !delay.is_zero() {
+        thread::sleep(delay);
+    }
+    let _ = request.respond(response);
+}
+
+fn respond(
+    st: &State,
+    req: &RecordedRequest,
+    failure: Option<Failure>,
+) -> Response<io::Cursor<Vec<u8>>> {
+    match failure {
+        Some(Failure::Unauthorized) => {
+            return json_response(401, &json!({ "error": "Unauthorized" }))
+        }
+        Some(Failure::RateLimited { retry_after }) => {
+            let header =
+                Header::from_bytes("Retry-After", retry_after.to_string()).expect("valid header");
+            return json_response(429, &json!({ "error": "Too many requests" }))
+                .with_header(header);
+        }
+        Some(Failure::ServerError) => {
+            return json_response(500, &json!({ "error": "Internal server error" }))
+        }
+        None => {}
+    }
+    if req.authorization.as_deref() != Some(&format!("Bearer {}", st.token)) {
+        return json_response(401, &json!({ "error": "Unauthorized" }));
+    }
+    let count = req
+        .param("count")
+        .and_then(|c| c.parse::<usize>().ok())
+        .unwrap_or(20);
+    match req.path.as_str() {
+        "/api/1.0/instances/sample" => {
+            let items: Vec<&Value> = st.instances.iter().take(count).collect();
+            json_response(200, &json!({ "instances": items }))
+        }
+        "/api/1.0/instances/list" => list(st, req, count),
+        _ => json_response(404, &json!({ "error": "Not found" })),
+    }
+}
+
+// Server-side filters of the real API: down and closed servers are left
+// out unless asked for, `language` matches the declared languages.
+fn list(st: &State, req: &RecordedRequest, count: usize) -> Response<io::Cursor<Vec<u8>>> {
+    let flag = |name: &str| req.param(name) == Some("true");
+    let language = req.param("language").filter(|l| !l.is_empty());
+    let min_id = req.param("min_id").and_then(|id| id.parse::<u64>().ok());
+    let matching: Vec<&Value> = st
+        .instances
+        .iter()
+        .filter(|i| flag("include_down") || i["up"].as_bool().unwrap_or(true))
+        .filter(|i| flag("include_closed") || i["open_registrations"].as_bool().unwrap_or(true))
+        .filter(|i| {
+            language.is_none_or(|lang| {
+                i["info"]["languages"]
+                    .as_array()
+                    .is_some_and(|ls| ls.iter().any(|l| l == lang))
+            })
+        })
+        .collect();
+    let total = matching.len();
+    let page: Vec<&Value> = matching
+        .iter()
+        .filter(|i| {
+            min_id.is_none_or(|min| {
+                i["id"]
+                    .as_str()
+                    .and_then(|id| id.parse::<u64>().ok())
+                    .is_some_and(|id| id > min)
+            })
+        })
+        .take(count.min(st.page_size))
+        .copied()
+        .collect();
+    let last = page.last().and_then(|i| i["id"].as_str());
+    let more = last.is_some_and(|last| {
+        matching
+            .iter()
+            .rev()
+            .find_map(|i| i["id"].as_str())
+            .is_some_and(|end| end != last)
+    });
+    let next_id = if more { last } else { None };
+    json_response(
+        200,
+        &json!({
+            "instances": page,
+            "pagination": { "total": total, "next_id": next_id },
+        }),
+    )
+}
@@ -1,401 +1,528 @@
-use std::{
-    fs,
-    path::PathBuf,
-    sync::RwLock,
-    time::{Duration, SystemTime},
-};
-
-use keyring::Entry;
-use reqwest::{blocking::Client as HttpClient, header::AUTHORIZATION};
-use serde::de::DeserializeOwned;
-use serde::{Deserialize, Serialize};
-use tauri::Manager;
-use thiserror::Error;
-
-const SERVICE: &str = "org.instances.finder";
-const USERNAME: &str = "instances_social_token";
-const API_BASE_URL: &str = "https://instances.social/api/1.0";
-const USER_AGENT: &str = concat!("instances-finder/", env!("CARGO_PKG_VERSION"));
-const HTTP_TIMEOUT_SECS: u64 = 20;
-
-#[derive(Debug, Error)]
-pub enum ApiError {
-    #[error("no token available")]
-    NoToken,
-}
-
-#[derive(Debug, Default)]
-pub struct AppState {
-    pub token: RwLock<Option<String>>,
-}
-
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
-pub struct FetchParams {
-    pub language: Option<String>,
-    pub include_closed: Option<bool>,
-    pub include_down: Option<bool>,
-    pub max: Option<usize>,
-    pub signups: Option<String>, // "open" | "approval"
-    pub region: Option<String>,  // "eu" | "na" | "other"
-    pub size: Option<String>,    // "small" | "medium" | "large"
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct JsInstance {
-    pub domain: String,
-    pub description: String,
-    pub languages: Vec<String>,
-    pub signups: String, // "open" | "approval"
-    pub size: u8,
-    #[serde(rename = "sizeLabel")]
-    pub size_label: String,
-    pub region: String,
-    pub availability: f32,
-}
-
-#[derive(Serialize, Deserialize)]
-struct CacheFile {
-    saved_at: u64,
-    params: FetchParams,
-    items: Vec<JsInstance>,
-}
-
-struct InstancesSocialClient {
-    http: HttpClient,
-    token: String,
-}
-
-impl InstancesSocialClient {
-    fn new(token: &str) -> Result<Self, String> {
-        let http = HttpClient::builder()
-            .user_agent(USER_AGENT)
-            .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
-            .build()
-            .map_err(|e| e.to_string())?;
-        Ok(Self {
-            http,
-            token: token.to_string(),
-        })
-    }
-
-    fn sample(&self, count: u64) -> Result<(), String> {
-        let mut query = Vec::new();
-        if count > 0 {
-            query.push(("count", count.to_string()));
-        }
-        self.get::<serde_json::Value>("instances/sample", &query)
-            .map(|_| ())
-    }
-
-    fn list(&self, params: &FetchParams, count: u64) -> Result<ListResponse, String> {
-        let mut query = vec![("count", count.to_string())];
-        if let Some(include_down) = params.include_down {
-            query.push(("include_down", include_down.to_string()));
-        }
-        if let Some(include_closed) = params.include_closed {
-            query.push(("include_closed", include_closed.to_string()));
-        }
-        if let Some(language) = &params.language {
-            if !language.trim().is_empty() {
-                query.push(("language", language.clone()));
-            }
-        }
-        self.get("instances/list", &query)
-    }
-
-    fn get<T>(&self, path: &str, query: &[(&str, String)]) -> Result<T, String>
-    where
-        T: DeserializeOwned,
-    {
-        let url = format!("{API_BASE_URL}/{path}");
-        let mut request = self.http.get(url);
-        if !query.is_empty() {
-            request = request.query(&query);
-        }
-        let response = request
-            .header(AUTHORIZATION, format!("Bearer {}", self.token))
-            .send()
-            .map_err(|e| e.to_string())?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let body = response.text().unwrap_or_default();
-            return Err(format!("instances.social returned {}: {}", status, body));
-        }
-        response.json().map_err(|e| e.to_string())
-    }
+use std::{collections::BTreeMap, fs, path::PathBuf, time::Duration};
+
+use tauri::{Emitter, Manager};
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::backend::{self, AdvisoriesInfo, ApiError, AppState};
+use crate::export::{self, ExportFormat};
+use crate::i18n::{self, localized, t, t_with, UiLocale};
+use crate::menu;
+use instances_finder_core::accessibility::{self, AccessibilityReport};
+use instances_finder_core::activity::{self, ActivityReport};
+use instances_finder_core::batch::{self, BatchResult, DomainListSource};
+use instances_finder_core::directory;
+use instances_finder_core::error::Error;
+use instances_finder_core::favorites::{Favorite, FavoriteView, FavoritesStore};
+use instances_finder_core::model::{FetchParams, JsInstance};
+use instances_finder_core::moderation::{self, ModerationReport};
+use instances_finder_core::nodeinfo::{self, SoftwareInfo};
+use instances_finder_core::notices::{self, ServiceNotice};
+use instances_finder_core::preview::{self, InstancePreview};
+use instances_finder_core::privacy::{self, PrivacySummary};
+use instances_finder_core::probe::{self, ProbeClient};
+use instances_finder_core::recent::RecentStore;
+use instances_finder_core::score::Preferences;
+use instances_finder_core::security::{self, SecuritySummary};
+use instances_finder_core::uptime::UptimeStats;
+use instances_finder_core::watchlist::{WatchChange, WatchItem, WatchlistStore};
+
+const WATCH_FIRST_CHECK_SECS: u64 = 60;
+const WATCH_INTERVAL_SECS: u64 = 6 * 3600;
+
+pub(crate) fn app_data_dir(app: &tauri::AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| std::env::temp_dir())
 }
 
-#[derive(Debug, Deserialize)]
-struct ListResponse {
-    instances: Vec<Instance>,
+pub(crate) fn favorites_store(app: &tauri::AppHandle) -> FavoritesStore {
+    backend::favorites_store(&app_data_dir(app))
 }
 
-#[derive(Clone, Debug, Deserialize)]
-struct Instance {
-    name: String,
-    up: bool,
-    users: String,
-    open_registrations: bool,
-    info: Option<InstanceInfo>,
+pub(crate) fn watchlist_store(app: &tauri::AppHandle) -> WatchlistStore {
+    backend::watchlist_store(&app_data_dir(app))
 }
 
-#[derive(Clone, Debug, Deserialize)]
-struct InstanceInfo {
-    short_description: Option<String>,
-    languages: Option<Vec<String>>,
+pub(crate) fn recent_store(app: &tauri::AppHandle) -> RecentStore {
+    backend::recent_store(&app_data_dir(app))
 }
 
-fn cache_path(app: &tauri::AppHandle) -> PathBuf {
-    let mut dir = app
-        .path()
-        .app_data_dir()
-        .unwrap_or_else(|_| std::env::temp_dir());
-    dir.push("instances-finder");
-    let _ = fs::create_dir_all(&dir);
-    dir.push("instances_cache.json");
-    dir
+// The probes behind the instance details: errors in the UI language
+fn with_client<T>(
+    locale: &UiLocale,
+    domain: &str,
+    f: impl FnOnce(&ProbeClient) -> Result<T, Error>,
+) -> Result<T, String> {
+    ProbeClient::new(domain)
+        .and_then(|client| f(&client))
+        .map_err(|e| locale.error(e))
 }
 
 #[tauri::command]
 pub fn token_status(state: tauri::State<'_, AppState>) -> bool {
-    if state.token.read().unwrap().is_some() {
-        return true;
-    }
-    match Entry::new(SERVICE, USERNAME) {
-        Ok(e) => e.get_password().is_ok(),
-        Err(_) => false,
-    }
+    state.token_status()
 }
 
 #[tauri::command]
 pub fn save_token(
     state: tauri::State<'_, AppState>,
+    locale: tauri::State<'_, UiLocale>,
     token: String,
     persist: bool,
 ) -> Result<(), String> {
-    if persist {
-        let entry = Entry::new(SERVICE, USERNAME).map_err(|e| e.to_string())?;
-        entry.set_password(&token).map_err(|e| e.to_string())?;
-    }
-    // also keep in memory for this session
-    *state.token.write().unwrap() = Some(token);
-    Ok(())
+    state
+        .save_token(token, persist)
+        .map_err(|e| locale.error(e))
 }
 
 #[tauri::command]
 pub fn clear_token(state: tauri::State<'_, AppState>) -> Result<(), String> {
-    if let Ok(entry) = Entry::new(SERVICE, USERNAME) {
-        // keyring v3 uses `delete_credential` instead of `delete_password`
-        let _ = entry.delete_credential();
-    }
-    *state.token.write().unwrap() = None;
+    state.clear_token();
     Ok(())
 }
 
-fn get_token(state: &tauri::State<'_, AppState>) -> Option<String> {
-    if let Some(t) = state.token.read().unwrap().clone() {
-        return Some(t);
-    }
-    if let Ok(entry) = Entry::new(SERVICE, USERNAME) {
-        entry.get_password().ok()
-    } else {
-        None
-    }
-}
-
 #[tauri::command]
-pub fn test_token(state: tauri::State<'_, AppState>, token: Option<String>) -> Result<(), String> {
-    let t = token
-        .or_else(|| get_token(&state))
-        .ok_or_else(|| ApiError::NoToken.to_string())?;
-    let client = InstancesSocialClient::new(&t)?;
-    client.sample(1)?;
-    Ok(())
+pub fn test_token(
+    state: tauri::State<'_, AppState>,
+    locale: tauri::State<'_, UiLocale>,
+    token: Option<String>,
+) -> Result<(), String> {
+    token
+        .map_or_else(|| state.token(), Ok)
+        .and_then(|t| Ok(directory::test_token(&t)?))
+        .map_err(|e| locale.error(e))
 }
 
 #[tauri::command]
-pub fn clear_instances_cache(app: tauri::AppHandle) -> Result<(), String> {
-    let p = cache_path(&app);
-    if p.exists() {
-        fs::remove_file(p).map_err(|e| e.to_string())?;
-    }
-    Ok(())
+pub fn clear_instances_cache(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+) -> Result<(), String> {
+    directory::clear_cache(&backend::cache_path(&app_data_dir(&app))).map_err(|e| locale.error(e))
 }
 
-#[tauri::command]
+#[tauri::command(async)]
 pub fn fetch_instances(
     app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
+    locale: tauri::State<'_, UiLocale>,
     params: FetchParams,
     bypass_cache: Option<bool>,
 ) -> Result<Vec<JsInstance>, String> {
-    // cache 24h (skip in debug or when bypass_cache=true)
-    let cache_file = cache_path(&app);
-    let want_cache = !bypass_cache.unwrap_or(false) && !cfg!(debug_assertions);
-    if want_cache {
-        if let Ok(bytes) = fs::read(&cache_file) {
-            if let Ok(cache) = serde_json::from_slice::<CacheFile>(&bytes) {
-                let age = SystemTime::UNIX_EPOCH + Duration::from_secs(cache.saved_at);
-                if age.elapsed().unwrap_or_default() < Duration::from_secs(24 * 3600)
-                    && cache.params == params
-                {
-                    return Ok(cache.items);
-                }
-            }
-        }
-    }
+    let items = backend::fetch_instances(
+        &app_data_dir(&app),
+        &state,
+        &params,
+        bypass_cache.unwrap_or(false),
+        locale.get(),
+    )
+    .map_err(|e| locale.error(e))?;
+    menu::rebuild(&app);
+    Ok(items)
+}
 
-    let t = get_token(&state).ok_or_else(|| ApiError::NoToken.to_string())?;
-    let client = InstancesSocialClient::new(&t)?;
-    let max = params.max.unwrap_or(200);
-    let resp = client.list(&params, max as u64)?;
-
-    fn map_region_from_domain(domain: &str) -> String {
-        let d = domain.to_lowercase();
-        let eu_tlds = [
-            ".eu", ".fr", ".de", ".it", ".es", ".pt", ".pl", ".nl", ".be", ".lu", ".ie", ".se",
-            ".fi", ".dk", ".cz", ".sk", ".si", ".hr", ".gr", ".bg", ".ro", ".hu", ".lt", ".lv",
-            ".ee", ".cy", ".mt", ".is", ".no",
-        ];
-        if d.ends_with(".us") || d.ends_with(".ca") {
-            return "na".into();
-        }
-        if eu_tlds.iter().any(|t| d.ends_with(t)) {
-            return "eu".into();
-        }
-        "other".into()
-    }
+#[tauri::command]
+pub fn fetch_languages(
+    state: tauri::State<'_, AppState>,
+    locale: tauri::State<'_, UiLocale>,
+) -> Result<Vec<String>, String> {
+    state
+        .token()
+        .and_then(|t| Ok(directory::languages(&t)?))
+        .map_err(|e| locale.error(e))
+}
 
-    let items: Vec<JsInstance> = resp
-        .instances
-        .into_iter()
-        .filter_map(|i| {
-            let users_i64 = i.users.parse::<i64>().unwrap_or(0);
-            let size = if users_i64 <= 2000 {
-                1
-            } else if users_i64 <= 10000 {
-                2
-            } else {
-                3
-            } as u8;
-            let size_label = if size == 1 {
-                "Petite"
-            } else if size == 2 {
-                "Moyenne"
-            } else {
-                "Grande"
-            };
-            let langs = i
-                .info
-                .as_ref()
-                .and_then(|inf| inf.languages.clone())
-                .unwrap_or_default();
-            let region = map_region_from_domain(&i.name);
-
-            // Client-side filters
-            if let Some(sig) = &params.signups {
-                if (sig == "open" && !i.open_registrations)
-                    || (sig == "approval" && i.open_registrations)
-                {
-                    return None;
-                }
-            }
-            if let Some(reg) = &params.region {
-                if &region != reg {
-                    return None;
-                }
-            }
-            if let Some(sz) = &params.size {
-                let want = if sz == "small" {
-                    1
-                } else if sz == "medium" {
-                    2
-                } else {
-                    3
-                };
-                // keep near target (allow +-1 only for medium)
-                if sz == "medium" {
-                    if !(size == 1 || size == 2) {
-                        return None;
-                    }
-                } else if size != want {
-                    return None;
-                }
-            }
-            if let Some(lang) = &params.language {
-                if !langs.iter().any(|l| l == lang) {
-                    return None;
-                }
-            }
+#[tauri::command(async)]
+pub fn fetch_moderation(
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<ModerationReport, String> {
+    with_client(&locale, &domain, moderation::moderation_report)
+}
 
-            Some(JsInstance {
-                domain: i.name.clone(),
-                description: i
-                    .info
-                    .as_ref()
-                    .and_then(|inf| inf.short_description.clone())
-                    .unwrap_or_default(),
-                languages: langs,
-                signups: if i.open_registrations {
-                    "open".into()
-                } else {
-                    "approval".into()
-                },
-                size,
-                size_label: size_label.into(),
-                region,
-                availability: if i.up { 0.999 } else { 0.4 },
-            })
-        })
-        .collect();
-
-    let cache = CacheFile {
-        saved_at: SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
-        params,
-        items: items.clone(),
-    };
-    if want_cache {
-        if let Ok(bytes) = serde_json::to_vec(&cache) {
-            let _ = fs::write(cache_file, bytes);
-        }
-    }
+#[tauri::command(async)]
+pub fn fetch_accessibility(
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<AccessibilityReport, String> {
+    with_client(&locale, &domain, accessibility::accessibility_report)
+}
 
-    Ok(items)
+#[tauri::command(async)]
+pub fn fetch_nodeinfo(
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<SoftwareInfo, String> {
+    with_client(&locale, &domain, nodeinfo::fetch_software)
+}
+
+#[tauri::command(async)]
+pub fn fetch_notice(
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<Option<ServiceNotice>, String> {
+    with_client(&locale, &domain, notices::detect_notice)
+}
+
+/// Notices of several servers at once, keyed by domain.
+#[tauri::command(async)]
+pub fn check_notices(domains: Vec<String>) -> BTreeMap<String, ServiceNotice> {
+    backend::check_notices(&domains)
+}
+
+#[tauri::command(async)]
+pub fn preview_instance(
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<InstancePreview, String> {
+    let labels = i18n::preview_labels(locale.get());
+    with_client(&locale, &domain, |client| preview::preview(client, &labels))
+}
+
+#[tauri::command(async)]
+pub fn fetch_activity(
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+    users: Option<u64>,
+) -> Result<ActivityReport, String> {
+    with_client(&locale, &domain, |client| {
+        activity::activity_report(client, users)
+    })
 }
 
 #[tauri::command]
-pub fn fetch_languages(state: tauri::State<'_, AppState>) -> Result<Vec<String>, String> {
-    let t = get_token(&state).ok_or_else(|| ApiError::NoToken.to_string())?;
-    let client = InstancesSocialClient::new(&t)?;
-    // Fetch a larger sample to discover languages
-    let resp = client.list(
-        &FetchParams {
-            language: None,
-            include_closed: None,
-            include_down: None,
-            max: None,
-            signups: None,
-            region: None,
-            size: None,
-        },
-        500,
-    )?;
-    use std::collections::BTreeSet;
-    let mut set = BTreeSet::new();
-    for i in resp.instances {
-        if let Some(info) = i.info {
-            if let Some(langs) = info.languages {
-                for l in langs {
-                    if !l.trim().is_empty() {
-                        set.insert(l.to_lowercase());
+pub fn normalize_domain(
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<String, String> {
+    probe::normalize_domain(&domain).map_err(|e| locale.error(e))
+}
+
+#[tauri::command(async)]
+pub fn evaluate_domain(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<JsInstance, String> {
+    backend::evaluate_domain(&app_data_dir(&app), &domain, locale.get())
+        .map_err(|e| locale.error(e))
+}
+
+/// Evaluate a pasted or file-based list of domains. Runs off the main thread
+/// so `batch://progress` events reach the webview while hosts are probed.
+#[tauri::command(async)]
+pub fn evaluate_domain_list(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    source: DomainListSource,
+    prefs: Option<Preferences>,
+) -> Result<BatchResult, String> {
+    let lang = locale.get();
+    let text = match source {
+        DomainListSource::Text { text } => text,
+        DomainListSource::File { path } => {
+            let path = match path {
+                Some(p) => PathBuf::from(p),
+                None => {
+                    let picked = app
+                        .dialog()
+                        .file()
+                        .add_filter(t(lang, "dialog.domain_lists"), &["txt", "csv"])
+                        .blocking_pick_file();
+                    match picked {
+                        Some(p) => p
+                            .into_path()
+                            .map_err(|_| locale.error(ApiError::NotLocalFile))?,
+                        // Dialog cancelled
+                        None => {
+                            return Ok(BatchResult {
+                                items: Vec::new(),
+                                errors: Vec::new(),
+                            })
+                        }
                     }
                 }
+            };
+            batch::read_domain_file(&path).map_err(|e| locale.error(e))?
+        }
+    };
+
+    Ok(backend::evaluate_domain_text(
+        &app_data_dir(&app),
+        &text,
+        &prefs.unwrap_or_default(),
+        lang,
+        &|progress| {
+            let _ = app.emit("batch://progress", progress);
+        },
+    ))
+}
+
+#[tauri::command(async)]
+pub fn audit_security(
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<SecuritySummary, String> {
+    with_client(&locale, &domain, security::audit)
+}
+
+#[tauri::command(async)]
+pub fn audit_privacy(
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<PrivacySummary, String> {
+    with_client(&locale, &domain, privacy::privacy_summary)
+}
+
+#[tauri::command]
+pub fn advisories_info(app: tauri::AppHandle) -> AdvisoriesInfo {
+    backend::advisories_info(&app_data_dir(&app))
+}
+
+/// Replace the advisory list with a newer JSON file (same format as
+/// `crates/core/data/advisories.json`). `None` asks the user with a file dialog;
+/// returns `None` if the dialog is cancelled.
+#[tauri::command(async)]
+pub fn import_advisories(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    path: Option<String>,
+) -> Result<Option<AdvisoriesInfo>, String> {
+    let path = match path {
+        Some(p) => PathBuf::from(p),
+        None => {
+            let picked = app
+                .dialog()
+                .file()
+                .add_filter(t(locale.get(), "dialog.advisory_list"), &["json"])
+                .blocking_pick_file();
+            match picked {
+                Some(p) => p
+                    .into_path()
+                    .map_err(|_| locale.error(ApiError::NotLocalFile))?,
+                None => return Ok(None),
             }
         }
+    };
+    backend::import_advisories(&app_data_dir(&app), &path)
+        .map(Some)
+        .map_err(|e| locale.error(e))
+}
+
+/// Probe each domain once, add the result to the local history and return
+/// the updated uptime and latency figures.
+#[tauri::command(async)]
+pub fn check_uptime(app: tauri::AppHandle, domains: Vec<String>) -> Vec<UptimeStats> {
+    backend::check_uptime(&app_data_dir(&app), &domains)
+}
+
+/// Uptime figures from the local history only, without probing.
+#[tauri::command]
+pub fn uptime_stats(app: tauri::AppHandle, domains: Vec<String>) -> Vec<UptimeStats> {
+    backend::uptime_stats(&app_data_dir(&app), &domains)
+}
+
+/// Forget the probe history of one domain, or of all when `domain` is unset.
+#[tauri::command]
+pub fn purge_uptime_history(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    domain: Option<String>,
+) -> Result<(), String> {
+    backend::purge_uptime_history(&app_data_dir(&app), domain.as_deref())
+        .map_err(|e| locale.error(e))
+}
+
+#[tauri::command]
+pub fn watchlist_list(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+) -> Result<Vec<WatchItem>, String> {
+    watchlist_store(&app).list().map_err(|e| locale.error(e))
+}
+
+#[tauri::command]
+pub fn watchlist_add(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<Vec<WatchItem>, String> {
+    let items = watchlist_store(&app)
+        .add(&domain)
+        .map_err(|e| locale.error(e))?;
+    menu::rebuild(&app);
+    Ok(items)
+}
+
+#[tauri::command]
+pub fn watchlist_remove(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<Vec<WatchItem>, String> {
+    let items = watchlist_store(&app)
+        .remove(&domain)
+        .map_err(|e| locale.error(e))?;
+    menu::rebuild(&app);
+    Ok(items)
+}
+
+/// Recheck the watchlist now instead of waiting for the background task.
+#[tauri::command(async)]
+pub fn watchlist_check(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+) -> Result<Vec<WatchItem>, String> {
+    check_watchlist(&app).map_err(|e| locale.error(e))?;
+    watchlist_store(&app).list().map_err(|e| locale.error(e))
+}
+
+fn check_watchlist(app: &tauri::AppHandle) -> Result<(), Error> {
+    let changes = watchlist_store(app).check_all()?;
+    // Modes shown in the menu may be new even without changes (first check)
+    menu::rebuild(app);
+    for change in &changes {
+        notify_watch_change(app, change);
+    }
+    if !changes.is_empty() {
+        let _ = app.emit("watchlist://changed", &changes);
     }
-    Ok(set.into_iter().collect())
+    Ok(())
+}
+
+fn notify_watch_change(app: &tauri::AppHandle, change: &WatchChange) {
+    let lang = app.state::<UiLocale>().get();
+    let from = i18n::signups(lang, &change.from);
+    let to = i18n::signups(lang, &change.to);
+    let _ = app
+        .notification()
+        .builder()
+        .title(t_with(
+            lang,
+            "notify.watch_title",
+            &[("domain", &change.domain), ("to", &to)],
+        ))
+        .body(t_with(
+            lang,
+            "notify.watch_body",
+            &[("from", &from), ("to", &to)],
+        ))
+        .show();
+}
+
+/// Recheck watched registrations shortly after startup, then on a schedule,
+/// for as long as the app runs.
+pub fn start_watchlist_task(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_secs(WATCH_FIRST_CHECK_SECS));
+        loop {
+            let _ = check_watchlist(&app);
+            std::thread::sleep(Duration::from_secs(WATCH_INTERVAL_SECS));
+        }
+    });
+}
+
+/// The shortlist in the user's order. With `refresh`, each favorite is
+/// probed again and compared with the snapshot taken when it was starred.
+#[tauri::command(async)]
+pub fn favorites_list(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    refresh: Option<bool>,
+) -> Result<Vec<FavoriteView>, String> {
+    backend::favorites_list(&app_data_dir(&app), refresh.unwrap_or(false), locale.get())
+        .map_err(|e| locale.error(e))
+}
+
+/// Star an instance; starring it again replaces the snapshot.
+#[tauri::command]
+pub fn favorites_add(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    instance: JsInstance,
+    note: Option<String>,
+) -> Result<Vec<Favorite>, String> {
+    let items = favorites_store(&app)
+        .add(instance, note)
+        .map_err(|e| locale.error(e))?;
+    menu::rebuild(&app);
+    Ok(localized(items, locale.get()))
+}
+
+#[tauri::command]
+pub fn favorites_remove(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+) -> Result<Vec<Favorite>, String> {
+    let items = favorites_store(&app)
+        .remove(&domain)
+        .map_err(|e| locale.error(e))?;
+    menu::rebuild(&app);
+    Ok(localized(items, locale.get()))
+}
+
+#[tauri::command]
+pub fn favorites_reorder(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    domains: Vec<String>,
+) -> Result<Vec<Favorite>, String> {
+    let items = favorites_store(&app)
+        .reorder(&domains)
+        .map_err(|e| locale.error(e))?;
+    menu::rebuild(&app);
+    Ok(localized(items, locale.get()))
+}
+
+#[tauri::command]
+pub fn favorites_set_note(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    domain: String,
+    note: String,
+) -> Result<Vec<Favorite>, String> {
+    favorites_store(&app)
+        .set_note(&domain, &note)
+        .map(|items| localized(items, locale.get()))
+        .map_err(|e| locale.error(e))
+}
+
+/// Write `items` (the ranked results, in display order) or, when `None`,
+/// the shortlist to a file chosen in a save dialog, labelled in the UI
+/// language. Returns the written path, or `None` if the dialog is cancelled.
+#[tauri::command(async)]
+pub fn export_results(
+    app: tauri::AppHandle,
+    locale: tauri::State<'_, UiLocale>,
+    items: Option<Vec<JsInstance>>,
+    format: ExportFormat,
+) -> Result<Option<String>, String> {
+    let lang = locale.get();
+    let (source, items) =
+        backend::export_items(&app_data_dir(&app), items, lang).map_err(|e| locale.error(e))?;
+    let picked = app
+        .dialog()
+        .file()
+        .add_filter(format.filter_name(), &[format.extension()])
+        .set_file_name(export::file_name(source, format))
+        .blocking_save_file();
+    let path = match picked {
+        Some(p) => p
+            .into_path()
+            .map_err(|_| locale.error(ApiError::NotLocalFile))?,
+        None => return Ok(None),
+    };
+    let body = export::render(&items, source, format, lang);
+    fs::write(&path, body).map_err(|e| locale.error(e))?;
+    Ok(Some(path.display().to_string()))
+}
+
+/// Follow the webview's language ("en", "fr"…) in the native menu and in
+/// the labels, errors and notifications produced here.
+#[tauri::command]
+pub fn set_locale(app: tauri::AppHandle, locale: tauri::State<'_, UiLocale>, lang: String) {
+    locale.set(&lang);
+    menu::rebuild(&app);
 }
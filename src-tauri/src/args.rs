@@ -0,0 +1,76 @@
+//! Command-line flags of the desktop app.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use instances_finder_core::error::Error;
+use instances_finder_core::link::{self, DeepLink};
+
+const SEARCH: &str = "Start with a search";
+const SORTS: [&str; 4] = ["relevance", "activity", "growth", "peers"];
+
+#[derive(Debug, Default, Parser)]
+#[command(name = "instances-finder", version, about = "Find Mastodon instances")]
+pub struct AppArgs {
+    /// instances-finder:// link to open (passed by the system)
+    #[arg(value_name = "LINK")]
+    pub link: Option<String>,
+    /// Serve the interface to a web browser instead of opening a window
+    #[arg(long)]
+    pub serve: bool,
+    /// Port for --serve (default: any free port)
+    #[arg(long, default_value_t = 0, requires = "serve")]
+    pub port: u16,
+    /// Serve this built frontend (`npm run build` output) instead of the bundled one
+    #[arg(long, value_name = "DIR", requires = "serve")]
+    pub dist: Option<PathBuf>,
+    /// Print the address without opening a browser
+    #[arg(long, requires = "serve")]
+    pub no_open: bool,
+
+    /// Language code(s) to search for, e.g. "fr" or "fr,en"
+    #[arg(long, help_heading = SEARCH)]
+    pub lang: Option<String>,
+    #[arg(long, help_heading = SEARCH, value_parser = ["small", "medium", "large"])]
+    pub size: Option<String>,
+    #[arg(long, help_heading = SEARCH, value_parser = ["open", "approval"])]
+    pub signups: Option<String>,
+    #[arg(long, help_heading = SEARCH, value_parser = ["eu", "na", "other"])]
+    pub region: Option<String>,
+    #[arg(long, help_heading = SEARCH, value_parser = ["open", "balanced", "strict"])]
+    pub moderation: Option<String>,
+    /// NodeInfo software family, e.g. "glitch-soc"
+    #[arg(long, help_heading = SEARCH)]
+    pub software: Option<String>,
+    /// Lowest accepted security grade
+    #[arg(long, help_heading = SEARCH, value_parser = ["A", "B", "C"])]
+    pub security: Option<String>,
+    #[arg(long, help_heading = SEARCH, value_parser = SORTS)]
+    pub sort: Option<String>,
+}
+
+impl AppArgs {
+    /// The search asked for with `--lang`, `--size`…, checked like the
+    /// query of an `instances-finder://search` link.
+    pub fn search(&self) -> Result<Option<DeepLink>, Error> {
+        let options = [
+            ("lang", &self.lang),
+            ("size", &self.size),
+            ("signups", &self.signups),
+            ("region", &self.region),
+            ("moderation", &self.moderation),
+            ("software", &self.software),
+            ("security", &self.security),
+            ("sort", &self.sort),
+        ];
+        let pairs: Vec<(String, String)> = options
+            .into_iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.clone()?)))
+            .collect();
+        if pairs.is_empty() {
+            return Ok(None);
+        }
+        link::search(&pairs).map(Some)
+    }
+}
@@ -0,0 +1,351 @@
+//! Command bodies shared by the Tauri commands (`api.rs`) and the browser
+//! server (`server.rs`), keyed by the app data directory.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::export::{ExportItem, ExportSource};
+use crate::i18n::localized;
+use instances_finder_core::advisories::AdvisoryList;
+use instances_finder_core::batch::{self, BatchProgress, BatchResult};
+use instances_finder_core::directory::{self, PROBE_WORKERS};
+use instances_finder_core::error::Error;
+use instances_finder_core::evaluate;
+use instances_finder_core::favorites::{self, FavoriteView, FavoritesStore};
+use instances_finder_core::model::{FetchParams, JsInstance};
+use instances_finder_core::notices::{self, ServiceNotice};
+use instances_finder_core::probe::{self, ProbeClient};
+use instances_finder_core::recent::RecentStore;
+use instances_finder_core::score::Preferences;
+use instances_finder_core::storage;
+use instances_finder_core::token;
+use instances_finder_core::uptime::{UptimeStats, UptimeStore};
+use instances_finder_core::watchlist::WatchlistStore;
+
+/// Errors of the commands: those of the core crate and the app's own. Each
+/// has a key in the `errors` section of the UI catalogs ([`Self::code`]).
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("no token available")]
+    NoToken,
+    #[error("advisory list from {date} is older than the bundled one")]
+    AdvisoriesOlder { date: String },
+    #[error("the chosen file is not on this device")]
+    NotLocalFile,
+    #[error("no file dialog in browser mode; paste the list instead")]
+    BrowserNoDialogList,
+    #[error("no file dialog in browser mode; give the path of the file")]
+    BrowserNoDialogFile,
+    #[error("invalid argument `{name}`: {error}")]
+    InvalidArgument { name: String, error: String },
+    #[error("unknown command `{command}`")]
+    UnknownCommand { command: String },
+    #[error(transparent)]
+    Core(#[from] Error),
+}
+
+impl ApiError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::NoToken => "no_token",
+            ApiError::AdvisoriesOlder { .. } => "advisories_older",
+            ApiError::NotLocalFile => "not_local_file",
+            ApiError::BrowserNoDialogList => "browser_no_dialog_list",
+            ApiError::BrowserNoDialogFile => "browser_no_dialog_file",
+            ApiError::InvalidArgument { .. } => "invalid_argument",
+            ApiError::UnknownCommand { .. } => "unknown_command",
+            ApiError::Core(e) => e.code(),
+        }
+    }
+
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            ApiError::AdvisoriesOlder { date } => vec![("date", date.clone())],
+            ApiError::InvalidArgument { name, error } => {
+                vec![("name", name.clone()), ("error", error.clone())]
+            }
+            ApiError::UnknownCommand { command } => vec![("command", command.clone())],
+            ApiError::Core(e) => e.params(),
+            ApiError::NoToken
+            | ApiError::NotLocalFile
+            | ApiError::BrowserNoDialogList
+            | ApiError::BrowserNoDialogFile => Vec::new(),
+        }
+    }
+}
+
+impl From<std::io::Error> for ApiError {
+    fn from(e: std::io::Error) -> Self {
+        ApiError::Core(e.into())
+    }
+}
+
+impl From<serde_json::Error> for ApiError {
+    fn from(e: serde_json::Error) -> Self {
+        ApiError::Core(e.into())
+    }
+}
+
+/// Session state: a token entered without saving it to the keyring.
+#[derive(Debug, Default)]
+pub struct AppState {
+    pub token: RwLock<Option<String>>,
+}
+
+impl AppState {
+    pub fn token_status(&self) -> bool {
+        self.token.read().unwrap().is_some() || token::load().is_some()
+    }
+
+    pub fn save_token(&self, token: String, persist: bool) -> Result<(), ApiError> {
+        if persist {
+            token::store(&token)?;
+        }
+        // also keep in memory for this session
+        *self.token.write().unwrap() = Some(token);
+        Ok(())
+    }
+
+    pub fn clear_token(&self) {
+        token::delete();
+        *self.token.write().unwrap() = None;
+    }
+
+    /// The session token, else the one saved in the keyring.
+    pub fn token(&self) -> Result<String, ApiError> {
+        self.token
+            .read()
+            .unwrap()
+            .clone()
+            .or_else(token::load)
+            .ok_or(ApiError::NoToken)
+    }
+}
+
+pub fn cache_path(dir: &Path) -> PathBuf {
+    storage::data_file(dir, storage::CACHE_FILE)
+}
+
+pub fn advisories_path(dir: &Path) -> PathBuf {
+    storage::data_file(dir, storage::ADVISORIES_FILE)
+}
+
+pub fn uptime_store(dir: &Path) -> UptimeStore {
+    UptimeStore::new(&storage::data_file(dir, storage::UPTIME_FILE))
+}
+
+pub fn favorites_store(dir: &Path) -> FavoritesStore {
+    FavoritesStore::new(&storage::data_file(dir, storage::FAVORITES_FILE))
+}
+
+pub fn watchlist_store(dir: &Path) -> WatchlistStore {
+    WatchlistStore::new(&storage::data_file(dir, storage::WATCHLIST_FILE))
+}
+
+pub fn recent_store(dir: &Path) -> RecentStore {
+    RecentStore::new(&storage::data_file(dir, storage::RECENT_FILE))
+}
+
+/// Directory results for `params`, labelled in `lang`.
+pub fn fetch_instances(
+    dir: &Path,
+    state: &AppState,
+    params: &FetchParams,
+    bypass_cache: bool,
+    lang: &str,
+) -> Result<Vec<JsInstance>, ApiError> {
+    // cache 24h (skip in debug or when bypass_cache=true)
+    let cache_file = cache_path(dir);
+    let want_cache = !bypass_cache && !cfg!(debug_assertions);
+    let cached = want_cache
+        .then(|| directory::read_cache(&cache_file, params))
+        .flatten();
+    let items = match cached {
+        Some(items) => items,
+        None => {
+            let t = state.token()?;
+            let advisories = AdvisoryList::load(&advisories_path(dir));
+            let items = directory::search(&t, params, &advisories)?;
+            if want_cache {
+                directory::write_cache(&cache_file, params, &items);
+            }
+            items
+        }
+    };
+    // Listed in the Recent searches menu
+    let _ = recent_store(dir).record(params);
+    Ok(localized(items, lang))
+}
+
+pub fn evaluate_domain(dir: &Path, domain: &str, lang: &str) -> Result<JsInstance, ApiError> {
+    let client = ProbeClient::new(domain)?;
+    let _ = uptime_store(dir).record(client.domain(), &client.ping());
+    let item = evaluate::evaluate(&client, &AdvisoryList::load(&advisories_path(dir)))?;
+    Ok(localized(item, lang))
+}
+
+/// Evaluate the domains listed in `text`, reporting each one to `progress`.
+pub fn evaluate_domain_text(
+    dir: &Path,
+    text: &str,
+    prefs: &Preferences,
+    lang: &str,
+    progress: &(dyn Fn(BatchProgress) + Sync),
+) -> BatchResult {
+    let (domains, mut errors) = batch::parse_domain_list(text);
+    let advisories = AdvisoryList::load(&advisories_path(dir));
+    let (items, failures) = batch::evaluate_domains(&domains, prefs, &advisories, progress);
+    errors.extend(failures);
+    localized(BatchResult { items, errors }, lang)
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdvisoriesInfo {
+    pub updated: String,
+    pub count: usize,
+    /// True when an imported list replaces the bundled one.
+    pub imported: bool,
+}
+
+fn advisories_info_for(list: &AdvisoryList) -> AdvisoriesInfo {
+    AdvisoriesInfo {
+        updated: list.updated.clone(),
+        count: list.advisories.len(),
+        imported: *list != AdvisoryList::bundled(),
+    }
+}
+
+pub fn advisories_info(dir: &Path) -> AdvisoriesInfo {
+    advisories_info_for(&AdvisoryList::load(&advisories_path(dir)))
+}
+
+/// Replace the advisory list with the JSON file at `path`, unless it is
+/// older than the bundled one.
+pub fn import_advisories(dir: &Path, path: &Path) -> Result<AdvisoriesInfo, ApiError> {
+    let bytes = fs::read(path)?;
+    let list = AdvisoryList::parse(&bytes)?;
+    if list.is_older_than(&AdvisoryList::bundled()) {
+        return Err(ApiError::AdvisoriesOlder { date: list.updated });
+    }
+    fs::write(advisories_path(dir), &bytes)?;
+    Ok(advisories_info_for(&list))
+}
+
+/// Notices published by `domains`, keyed by domain as given; servers that
+/// cannot be probed or publish none are left out.
+pub fn check_notices(domains: &[String]) -> BTreeMap<String, ServiceNotice> {
+    let notices = probe::parallel_map(domains, PROBE_WORKERS, |domain| {
+        let client = ProbeClient::new(domain).ok()?;
+        notices::detect_notice(&client).ok().flatten()
+    });
+    domains
+        .iter()
+        .zip(notices)
+        .filter_map(|(domain, notice)| Some((domain.clone(), notice?)))
+        .collect()
+}
+
+pub fn check_uptime(dir: &Path, domains: &[String]) -> Vec<UptimeStats> {
+    let store = uptime_store(dir);
+    probe::parallel_map(domains, PROBE_WORKERS, |domain| {
+        let client = ProbeClient::new(domain).ok()?;
+        let _ = store.record(client.domain(), &client.ping());
+        Some(store.stats(client.domain()))
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+pub fn uptime_stats(dir: &Path, domains: &[String]) -> Vec<UptimeStats> {
+    let store = uptime_store(dir);
+    domains
+        .iter()
+        .filter_map(|d| probe::normalize_domain(d).ok())
+        .map(|d| store.stats(&d))
+        .collect()
+}
+
+pub fn purge_uptime_history(dir: &Path, domain: Option<&str>) -> Result<(), ApiError> {
+    let domain = domain.map(probe::normalize_domain).transpose()?;
+    Ok(uptime_store(dir).purge(domain.as_deref())?)
+}
+
+/// The shortlist labelled in `lang`; snapshots are relabelled before they
+/// are compared, so a language change is not reported as a change.
+pub fn favorites_list(
+    dir: &Path,
+    refresh: bool,
+    lang: &str,
+) -> Result<Vec<FavoriteView>, ApiError> {
+    let items = localized(favorites_store(dir).list()?, lang);
+    if !refresh {
+        return Ok(items
+            .into_iter()
+            .map(|favorite| FavoriteView {
+                favorite,
+                current: None,
+                changes: Vec::new(),
+            })
+            .collect());
+    }
+    let advisories = AdvisoryList::load(&advisories_path(dir));
+    let uptime = uptime_store(dir);
+    Ok(probe::parallel_map(&items, PROBE_WORKERS, |favorite| {
+        let current = ProbeClient::new(&favorite.domain).ok().and_then(|client| {
+            // A shortlisted instance is worth tracking in the uptime history
+            let _ = uptime.record(client.domain(), &client.ping());
+            evaluate::evaluate(&client, &advisories)
+                .ok()
+                .map(|current| localized(current, lang))
+        });
+        let changes = current
+            .as_ref()
+            .map(|c| favorites::diff(&favorite.snapshot, c))
+            .unwrap_or_default();
+        FavoriteView {
+            favorite: favorite.clone(),
+            current,
+            changes,
+        }
+    }))
+}
+
+/// What `export_results` writes: `items` as results, or the shortlist with
+/// its notes when `None`, labelled in `lang`.
+pub fn export_items(
+    dir: &Path,
+    items: Option<Vec<JsInstance>>,
+    lang: &str,
+) -> Result<(ExportSource, Vec<ExportItem>), ApiError> {
+    Ok(match items {
+        Some(items) => (
+            ExportSource::Results,
+            items
+                .into_iter()
+                .map(|instance| ExportItem {
+                    instance: localized(instance, lang),
+                    note: None,
+                })
+                .collect(),
+        ),
+        None => (
+            ExportSource::Shortlist,
+            favorites_store(dir)
+                .list()?
+                .into_iter()
+                .map(|f| ExportItem {
+                    instance: localized(f.snapshot, lang),
+                    note: Some(f.note),
+                })
+                .collect(),
+        ),
+    })
+}
@@ -0,0 +1,87 @@
+//! `instances-finder://` links opened from a web page or another app, and
+//! searches asked for on the command line (`--lang fr --size small`).
+//!
+//! A link can arrive before the webview has loaded, so it is kept until the
+//! frontend takes it (`take_deep_link`); `deep-link://open` tells an
+//! already loaded page that one is waiting.
+
+use std::sync::Mutex;
+
+use clap::Parser;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::args::AppArgs;
+use crate::i18n::UiLocale;
+use instances_finder_core::error::Error;
+use instances_finder_core::link::{self, DeepLink};
+
+#[derive(Debug, Default)]
+pub struct PendingLink(Mutex<Option<DeepLink>>);
+
+/// Handle the link the app was started with, then every later one.
+pub fn setup(app: &AppHandle) {
+    // Installers register the scheme; this covers dev builds and AppImages
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        eprintln!("could not register the {} scheme: {e}", link::SCHEME);
+    }
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            open(app, url.as_str());
+        }
+    }
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open(&handle, url.as_str());
+        }
+    });
+}
+
+/// Validate `url` and hand it to the webview.
+pub fn open(app: &AppHandle, url: &str) {
+    match link::parse(url) {
+        Ok(parsed) => deliver(app, parsed),
+        Err(e) => reject(app, &format!("ignored link {url}"), e),
+    }
+}
+
+/// Keep `link` for the webview and bring the window forward.
+pub fn deliver(app: &AppHandle, link: DeepLink) {
+    *app.state::<PendingLink>().0.lock().unwrap() = Some(link.clone());
+    let _ = app.emit("deep-link://open", link);
+    focus_main_window(app);
+}
+
+fn reject(app: &AppHandle, context: &str, error: Error) {
+    eprintln!("{context}: {error}");
+    let _ = app.emit("deep-link://invalid", app.state::<UiLocale>().error(error));
+}
+
+fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Called in the running app when it is launched again: focus it and run
+/// the search given on the new command line. Links on that command line
+/// reach `on_open_url` through the single-instance plugin.
+pub fn second_launch(app: &AppHandle, argv: Vec<String>) {
+    focus_main_window(app);
+    match AppArgs::try_parse_from(argv).map(|args| args.search()) {
+        Ok(Ok(Some(search))) => deliver(app, search),
+        Ok(Ok(None)) => {}
+        Ok(Err(e)) => reject(app, "ignored search options", e),
+        Err(e) => eprintln!("ignored arguments: {e}"),
+    }
+}
+
+/// The link waiting to be applied, if any; it is only returned once.
+#[tauri::command]
+pub fn take_deep_link(pending: tauri::State<'_, PendingLink>) -> Option<DeepLink> {
+    pending.0.lock().unwrap().take()
+}
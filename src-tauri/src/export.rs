@@ -0,0 +1,358 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::i18n::{size_key, t, t_with};
+use instances_finder_core::model::JsInstance;
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+        }
+    }
+
+    pub fn filter_name(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Html => "HTML",
+        }
+    }
+}
+
+/// One exported line: an instance and, for the shortlist, the user's note.
+pub struct ExportItem {
+    pub instance: JsInstance,
+    pub note: Option<String>,
+}
+
+/// What is being exported, which sets the title and the note column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportSource {
+    Results,
+    Shortlist,
+}
+
+struct Table {
+    title: String,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+/// Render `items` in `format`, with labels in `lang` ("en" or "fr").
+pub fn render(
+    items: &[ExportItem],
+    source: ExportSource,
+    format: ExportFormat,
+    lang: &str,
+) -> String {
+    match format {
+        ExportFormat::Json => to_json(items),
+        ExportFormat::Csv => to_csv(&table(items, source, lang)),
+        ExportFormat::Markdown => to_markdown(&table(items, source, lang)),
+        ExportFormat::Html => to_html(items, &table(items, source, lang), lang),
+    }
+}
+
+/// Suggested file name for the save dialog.
+pub fn file_name(source: ExportSource, format: ExportFormat) -> String {
+    let stem = match source {
+        ExportSource::Results => "instances",
+        ExportSource::Shortlist => "shortlist",
+    };
+    format!("{stem}.{}", format.extension())
+}
+
+fn table(items: &[ExportItem], source: ExportSource, lang: &str) -> Table {
+    let title = match source {
+        ExportSource::Results => t(lang, "export.title_results"),
+        ExportSource::Shortlist => t(lang, "export.title_shortlist"),
+    };
+    let mut headers: Vec<String> = [
+        "export.col_rank",
+        "results.col_domain",
+        "export.col_description",
+        "results.col_languages",
+        "results.col_signups",
+        "results.col_size",
+        "export.col_users",
+        "export.col_security",
+        "export.col_vulnerabilities",
+    ]
+    .iter()
+    .map(|k| t(lang, k))
+    .collect();
+    if source == ExportSource::Shortlist {
+        headers.push(t(lang, "export.col_note"));
+    }
+    let rows = items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let it = &item.instance;
+            let mut row = vec![
+                (idx + 1).to_string(),
+                it.domain.clone(),
+                it.description.clone(),
+                it.languages.join(", "),
+                t(lang, signups_key(&it.signups)),
+                t(lang, size_key(it.size)),
+                it.users.map(|u| u.to_string()).unwrap_or_default(),
+                it.security
+                    .as_ref()
+                    .map(|s| s.grade.to_string())
+                    .unwrap_or_default(),
+                it.vulnerability
+                    .as_ref()
+                    .map(|v| {
+                        v.advisories
+                            .iter()
+                            .map(|a| a.id.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .unwrap_or_default(),
+            ];
+            if source == ExportSource::Shortlist {
+                row.push(item.note.clone().unwrap_or_default());
+            }
+            row
+        })
+        .collect();
+    Table {
+        title,
+        headers,
+        rows,
+    }
+}
+
+fn signups_key(signups: &str) -> &'static str {
+    match signups {
+        "open" => "wizard.signups_open",
+        "approval" => "wizard.signups_approval",
+        _ => "export.signups_closed",
+    }
+}
+
+// JSON keeps the backend field names so it can be read back by tools.
+fn to_json(items: &[ExportItem]) -> String {
+    let list: Vec<Value> = items
+        .iter()
+        .map(|item| {
+            let mut value = serde_json::to_value(&item.instance).unwrap_or(Value::Null);
+            if let (Some(note), Value::Object(map)) = (&item.note, &mut value) {
+                map.insert("note".into(), Value::String(note.clone()));
+            }
+            value
+        })
+        .collect();
+    serde_json::to_string_pretty(&list).unwrap_or_default() + "\n"
+}
+
+// A leading quote keeps spreadsheets from running a cell as a formula.
+fn csv_field(s: &str) -> String {
+    let s = if s.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{s}")
+    } else {
+        s.to_string()
+    };
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s
+    }
+}
+
+// RFC 4180, with a byte order mark so spreadsheets detect UTF-8.
+fn to_csv(table: &Table) -> String {
+    let mut out = String::from("\u{feff}");
+    for line in std::iter::once(&table.headers).chain(&table.rows) {
+        let fields: Vec<String> = line.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn markdown_cell(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+}
+
+fn to_markdown(table: &Table) -> String {
+    let mut out = format!("# {}\n\n", markdown_cell(&table.title));
+    let row = |cells: &[String]| {
+        let cells: Vec<String> = cells.iter().map(|c| markdown_cell(c)).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    out.push_str(&row(&table.headers));
+    out.push_str(&format!("|{}\n", " --- |".repeat(table.headers.len())));
+    for r in &table.rows {
+        out.push_str(&row(r));
+    }
+    out
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem;line-height:1.5}\
+table{border-collapse:collapse}\
+th,td{border:1px solid #666;padding:.4rem .6rem;text-align:start;vertical-align:top}\
+thead th{background:#eee}";
+
+// Standalone report: document language, a caption, column and row headers,
+// and the description tagged with the instance language when there is one.
+fn to_html(items: &[ExportItem], table: &Table, lang: &str) -> String {
+    let lang = crate::i18n::normalize_lang(lang);
+    let caption = t_with(
+        lang,
+        "export.caption",
+        &[("count", &table.rows.len().to_string())],
+    );
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"{lang}\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<main>\n\
+         <h1>{title}</h1>\n<table>\n<caption>{caption}</caption>\n<thead>\n<tr>",
+        title = escape(&table.title),
+        caption = escape(&caption),
+    );
+    for h in &table.headers {
+        out.push_str(&format!("<th scope=\"col\">{}</th>", escape(h)));
+    }
+    out.push_str("</tr>\n</thead>\n<tbody>\n");
+    for (item, row) in items.iter().zip(&table.rows) {
+        let languages = &item.instance.languages;
+        out.push_str("<tr>");
+        for (col, cell) in row.iter().enumerate() {
+            match col {
+                1 => out.push_str(&format!(
+                    "<th scope=\"row\"><a href=\"https://{0}\">{0}</a></th>",
+                    escape(cell)
+                )),
+                2 if languages.len() == 1 && languages[0] != lang => out.push_str(&format!(
+                    "<td lang=\"{}\">{}</td>",
+                    escape(&languages[0]),
+                    escape(cell)
+                )),
+                _ => out.push_str(&format!("<td>{}</td>", escape(cell))),
+            }
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</tbody>\n</table>\n</main>\n</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(domain: &str, description: &str, languages: &[&str]) -> ExportItem {
+        let instance = serde_json::from_value(serde_json::json!({
+            "domain": domain,
+            "description": description,
+            "languages": languages,
+            "signups": "open",
+            "size": 1,
+            "sizeLabel": "small",
+            "region": "eu",
+            "availability": 0.999,
+        }))
+        .unwrap();
+        ExportItem {
+            instance,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn csv_quotes_and_defuses_formulas() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        for formula in ["=1+1", "+33 1", "-2", "@SUM(A1)"] {
+            assert_eq!(csv_field(formula), format!("'{formula}"));
+        }
+        assert_eq!(csv_field("\t=1+1"), "'\t=1+1");
+        assert_eq!(csv_field("\r=1+1"), "\"'\r=1+1\"");
+        assert_eq!(
+            csv_field("=HYPERLINK(\"x\",1)"),
+            "\"'=HYPERLINK(\"\"x\"\",1)\""
+        );
+
+        let out = render(
+            &[item("a.example", "=cmd, \"quoted\"", &["en"])],
+            ExportSource::Results,
+            ExportFormat::Csv,
+            "en",
+        );
+        assert!(out.starts_with("\u{feff}Rank,Domain,"));
+        assert!(out.contains(",\"'=cmd, \"\"quoted\"\"\","));
+        assert!(out.ends_with("\r\n"));
+    }
+
+    #[test]
+    fn markdown_escapes_cells() {
+        assert_eq!(markdown_cell("a | b"), "a \\| b");
+        assert_eq!(markdown_cell("back\\slash"), "back\\\\slash");
+        assert_eq!(markdown_cell("two\n  lines"), "two lines");
+
+        let out = render(
+            &[item("a.example", "pipes | here", &["en"])],
+            ExportSource::Shortlist,
+            ExportFormat::Markdown,
+            "en",
+        );
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "# Shortlisted Mastodon instances");
+        assert!(lines[2].ends_with("| Note |"));
+        assert_eq!(lines[3], format!("|{}", " --- |".repeat(10)));
+        assert!(lines[4].contains("| pipes \\| here |"));
+    }
+
+    #[test]
+    fn html_has_language_headers_and_escaping() {
+        let out = render(
+            &[
+                item("a.example", "<b>Bienvenue</b> & co", &["fr"]),
+                item("b.example", "Hello", &["en"]),
+            ],
+            ExportSource::Results,
+            ExportFormat::Html,
+            "en",
+        );
+        assert!(out.contains("<html lang=\"en\">"));
+        assert!(out.contains("<caption>2 instances</caption>"));
+        assert!(out.contains("<th scope=\"col\">Domain</th>"));
+        assert!(out.contains("<th scope=\"row\"><a href=\"https://a.example\">a.example</a></th>"));
+        assert!(out.contains("<td lang=\"fr\">&lt;b&gt;Bienvenue&lt;/b&gt; &amp; co</td>"));
+        assert!(out.contains("<td>Hello</td>"));
+        assert!(!out.contains("<b>"));
+
+        let out = render(&[], ExportSource::Results, ExportFormat::Html, "fr-CA");
+        assert!(out.contains("<html lang=\"fr\">"));
+    }
+}
@@ -0,0 +1,290 @@
+use std::sync::{OnceLock, RwLock};
+
+use serde_json::Value;
+
+use crate::backend::ApiError;
+use instances_finder_core::batch::BatchResult;
+use instances_finder_core::favorites::Favorite;
+use instances_finder_core::model::JsInstance;
+use instances_finder_core::preview::PreviewLabels;
+
+// The UI catalogs, so the backend says things the same way the interface does.
+const EN: &str = include_str!("../../src/locales/en.json");
+const FR: &str = include_str!("../../src/locales/fr.json");
+
+pub const DEFAULT_LANG: &str = "en";
+
+fn catalog(lang: &str) -> &'static Value {
+    static EN_CATALOG: OnceLock<Value> = OnceLock::new();
+    static FR_CATALOG: OnceLock<Value> = OnceLock::new();
+    let (cell, src) = match lang {
+        "fr" => (&FR_CATALOG, FR),
+        _ => (&EN_CATALOG, EN),
+    };
+    cell.get_or_init(|| serde_json::from_str(src).expect("locale catalog is valid JSON"))
+}
+
+/// Map a BCP 47 tag ("fr-CA", "en_US") to a supported UI language.
+pub fn normalize_lang(lang: &str) -> &'static str {
+    let primary = lang
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match primary.as_str() {
+        "fr" => "fr",
+        _ => DEFAULT_LANG,
+    }
+}
+
+/// UI language of the operating system, for what is shown before the
+/// webview reports its own.
+pub fn system_lang() -> &'static str {
+    sys_locale::get_locale()
+        .map(|locale| normalize_lang(&locale))
+        .unwrap_or(DEFAULT_LANG)
+}
+
+fn lookup<'a>(catalog: &'a Value, key: &str) -> Option<&'a str> {
+    key.split('.')
+        .try_fold(catalog, |node, part| node.get(part))
+        .and_then(Value::as_str)
+}
+
+/// Translate a dotted key ("results.col_domain"), falling back to English
+/// and then to the key itself.
+pub fn t(lang: &str, key: &str) -> String {
+    lookup(catalog(normalize_lang(lang)), key)
+        .or_else(|| lookup(catalog(DEFAULT_LANG), key))
+        .unwrap_or(key)
+        .to_string()
+}
+
+/// Translate and replace `{name}` placeholders.
+pub fn t_with(lang: &str, key: &str, params: &[(&str, &str)]) -> String {
+    params.iter().fold(t(lang, key), |s, (name, value)| {
+        s.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+/// Language of the interface: the OS locale until the webview reports its
+/// own. The menu, notifications and command errors follow it.
+#[derive(Debug)]
+pub struct UiLocale(RwLock<&'static str>);
+
+impl Default for UiLocale {
+    fn default() -> Self {
+        UiLocale(RwLock::new(system_lang()))
+    }
+}
+
+impl UiLocale {
+    pub fn get(&self) -> &'static str {
+        *self.0.read().unwrap()
+    }
+
+    pub fn set(&self, lang: &str) {
+        *self.0.write().unwrap() = normalize_lang(lang);
+    }
+
+    /// `error` in the interface language; see [`error`].
+    pub fn error(&self, e: impl Into<ApiError>) -> String {
+        error(self.get(), &e.into())
+    }
+}
+
+/// Message of `error` in `lang`, from its code in the `errors` section of
+/// the catalogs.
+pub fn error(lang: &str, error: &ApiError) -> String {
+    let params = error.params();
+    let params: Vec<(&str, &str)> = params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    t_with(lang, &format!("errors.{}", error.code()), &params)
+}
+
+pub fn size_key(size: u8) -> &'static str {
+    match size {
+        1 => "wizard.size_small",
+        2 => "wizard.size_medium",
+        _ => "wizard.size_large",
+    }
+}
+
+/// Registration mode as shown in results: "open", "approval" or "closed".
+pub fn signups(lang: &str, mode: &str) -> String {
+    match mode {
+        "open" | "approval" => t(lang, &format!("results.{mode}")),
+        _ => t(lang, "results.closed"),
+    }
+}
+
+pub fn preview_labels(lang: &str) -> PreviewLabels {
+    PreviewLabels {
+        image: t(lang, "preview.media_image"),
+        video: t(lang, "preview.media_video"),
+        gif: t(lang, "preview.media_gif"),
+        audio: t(lang, "preview.media_audio"),
+        no_description: t(lang, "preview.media_no_description"),
+        sensitive: t(lang, "preview.sensitive"),
+        link: t(lang, "preview.link"),
+    }
+}
+
+/// Core values that carry words, relabelled in `lang` before they are
+/// sent to the webview.
+pub trait Localize {
+    fn localize(&mut self, lang: &str);
+}
+
+impl Localize for JsInstance {
+    fn localize(&mut self, lang: &str) {
+        self.size_label = t(lang, size_key(self.size));
+        if let Some(security) = &mut self.security {
+            if let Some(failure) = &security.certificate_failure {
+                security.certificate_error = Some(error(lang, &failure.clone().into()));
+            }
+        }
+    }
+}
+
+impl Localize for Favorite {
+    fn localize(&mut self, lang: &str) {
+        self.snapshot.localize(lang);
+    }
+}
+
+impl Localize for BatchResult {
+    fn localize(&mut self, lang: &str) {
+        self.items.localize(lang);
+        for failure in &mut self.errors {
+            failure.reason = error(lang, &failure.error.clone().into());
+        }
+    }
+}
+
+impl<T: Localize> Localize for Vec<T> {
+    fn localize(&mut self, lang: &str) {
+        self.iter_mut().for_each(|item| item.localize(lang));
+    }
+}
+
+impl<T: Localize> Localize for Option<T> {
+    fn localize(&mut self, lang: &str) {
+        if let Some(item) = self {
+            item.localize(lang);
+        }
+    }
+}
+
+/// `value` relabelled in `lang`.
+pub fn localized<T: Localize>(mut value: T, lang: &str) -> T {
+    value.localize(lang);
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use instances_finder_core::error::Error;
+
+    fn errors() -> Vec<ApiError> {
+        let s = || "x".to_string();
+        let core = [
+            Error::RateLimitedRetry { secs: 30 },
+            Error::RateLimited,
+            Error::DirectoryStatus {
+                status: s(),
+                body: s(),
+            },
+            Error::HttpStatus {
+                url: s(),
+                status: s(),
+            },
+            Error::TimedOut { domain: s() },
+            Error::Connect { domain: s() },
+            Error::BadResponse { domain: s() },
+            Error::Network { domain: s() },
+            Error::NotHttps {
+                domain: s(),
+                url: s(),
+            },
+            Error::InvalidDomain { domain: s() },
+            Error::NoResolve { domain: s() },
+            Error::CertificateExpired { domain: s() },
+            Error::CertificateNotValidYet { domain: s() },
+            Error::CertificateUntrusted { domain: s() },
+            Error::CertificateWrongName { domain: s() },
+            Error::CertificateRevoked { domain: s() },
+            Error::CertificateInvalid { domain: s() },
+            Error::TlsHandshake { domain: s() },
+            Error::NoInstanceApi,
+            Error::NoNodeinfo,
+            Error::NodeinfoSchema,
+            Error::NodeinfoMissing,
+            Error::UnsupportedFile { path: s() },
+            Error::FileTooLarge { path: s() },
+            Error::FileNotFound,
+            Error::PermissionDenied,
+            Error::Io { error: s() },
+            Error::Data { error: s() },
+            Error::Internal { error: s() },
+            Error::AdvisoryDate { date: s() },
+            Error::AdvisorySeverity {
+                id: s(),
+                severity: s(),
+            },
+            Error::AdvisoryVersions { id: s() },
+            Error::NotFavorite { domain: s() },
+            Error::FileVersion {
+                path: s(),
+                version: 2,
+                supported: 1,
+            },
+            Error::Keyring { error: s() },
+            Error::LinkTooLong,
+            Error::InvalidLink { error: s() },
+            Error::NotOurLink,
+            Error::LinkParts,
+            Error::LinkQuery,
+            Error::UnknownLink,
+            Error::RepeatedParameter { name: s() },
+            Error::InvalidValue {
+                name: s(),
+                value: s(),
+            },
+            Error::UnknownParameter { name: s() },
+        ];
+        let app = [
+            ApiError::NoToken,
+            ApiError::AdvisoriesOlder { date: s() },
+            ApiError::NotLocalFile,
+            ApiError::BrowserNoDialogList,
+            ApiError::BrowserNoDialogFile,
+            ApiError::InvalidArgument {
+                name: s(),
+                error: s(),
+            },
+            ApiError::UnknownCommand { command: s() },
+        ];
+        core.into_iter().map(ApiError::from).chain(app).collect()
+    }
+
+    #[test]
+    fn english_catalog_matches_the_error_messages() {
+        for e in errors() {
+            assert_eq!(error("en", &e), e.to_string(), "{}", e.code());
+        }
+    }
+
+    #[test]
+    fn errors_are_translated_with_their_values() {
+        for e in errors() {
+            let fr = error("fr", &e);
+            assert!(!fr.starts_with("errors."), "{fr}");
+            assert!(!fr.contains('{'), "{fr}");
+        }
+        let e = ApiError::from(Error::TimedOut {
+            domain: "a.example".into(),
+        });
+        assert_eq!(error("fr", &e), "a.example n'a pas répondu à temps");
+    }
+}
@@ -0,0 +1,137 @@
+mod api;
+mod args;
+mod backend;
+mod deep_link;
+mod export;
+mod i18n;
+mod menu;
+mod server;
+mod window_state;
+
+use api::{
+    advisories_info, audit_privacy, audit_security, check_notices, check_uptime,
+    clear_instances_cache, clear_token, evaluate_domain, evaluate_domain_list, export_results,
+    favorites_add, favorites_list, favorites_remove, favorites_reorder, favorites_set_note,
+    fetch_accessibility, fetch_activity, fetch_instances, fetch_languages, fetch_moderation,
+    fetch_nodeinfo, fetch_notice, import_advisories, normalize_domain, preview_instance,
+    purge_uptime_history, save_token, set_locale, start_watchlist_task, test_token, token_status,
+    uptime_stats, watchlist_add, watchlist_check, watchlist_list, watchlist_remove,
+};
+use args::AppArgs;
+use backend::AppState;
+use clap::Parser;
+use deep_link::{take_deep_link, PendingLink};
+use i18n::UiLocale;
+use server::ServeOptions;
+use tauri::{Manager, WindowEvent};
+use window_state::TrackedState;
+
+/// Start the desktop app, or the browser server with `--serve`.
+pub fn run() {
+    let args = AppArgs::parse();
+    let context = tauri::generate_context!();
+    if args.serve {
+        let options = ServeOptions {
+            port: args.port,
+            open_browser: !args.no_open,
+        };
+        let result = match &args.dist {
+            Some(dir) => server::serve(&options, &|path| server::dir_assets(dir, path)),
+            None => {
+                let assets = context.assets();
+                server::serve(&options, &|path| {
+                    assets.get(&path.into()).map(|bytes| bytes.into_owned())
+                })
+            }
+        };
+        if let Err(e) = result {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let search = match args.search() {
+        Ok(search) => search,
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    tauri::Builder::default()
+        // First, so a second launch exits before setting anything up
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            deep_link::second_launch(app, argv)
+        }))
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .manage(AppState::default())
+        .manage(PendingLink::default())
+        .manage(TrackedState::default())
+        .manage(UiLocale::default())
+        .invoke_handler(tauri::generate_handler![
+            token_status,
+            save_token,
+            clear_token,
+            test_token,
+            fetch_instances,
+            fetch_languages,
+            fetch_moderation,
+            fetch_accessibility,
+            fetch_activity,
+            fetch_nodeinfo,
+            fetch_notice,
+            check_notices,
+            preview_instance,
+            normalize_domain,
+            evaluate_domain,
+            evaluate_domain_list,
+            audit_security,
+            audit_privacy,
+            advisories_info,
+            import_advisories,
+            check_uptime,
+            uptime_stats,
+            purge_uptime_history,
+            watchlist_list,
+            watchlist_add,
+            watchlist_remove,
+            watchlist_check,
+            favorites_list,
+            favorites_add,
+            favorites_remove,
+            favorites_reorder,
+            favorites_set_note,
+            export_results,
+            clear_instances_cache,
+            take_deep_link,
+            set_locale
+        ])
+        .setup(|app| {
+            if let Some(window) = app.get_webview_window("main") {
+                window_state::restore(&window);
+            }
+            start_watchlist_task(app.handle().clone());
+            deep_link::setup(app.handle());
+            if let Some(search) = search {
+                deep_link::deliver(app.handle(), search);
+            }
+            Ok(())
+        })
+        .menu(menu::build)
+        .on_window_event(|window, event| match event {
+            WindowEvent::Moved(_) | WindowEvent::Resized(_) => window_state::track(window),
+            WindowEvent::CloseRequested { .. } => {
+                window_state::track(window);
+                window_state::save(window.app_handle());
+            }
+            _ => {}
+        })
+        .on_menu_event(|app, event| menu::handle_event(app, event.id.as_ref()))
+        .run(context)
+        .expect("error while running tauri application");
+}
@@ -0,0 +1,326 @@
+use serde::Serialize;
+use tauri::{
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
+    AppHandle, Emitter, Manager, Wry,
+};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+use crate::backend::ApiError;
+use crate::i18n::{t, t_with, UiLocale};
+use crate::{api, backend, deep_link, window_state};
+use instances_finder_core::directory;
+use instances_finder_core::recent::RecentSearch;
+
+/// What a menu item asks the webview to do, sent as `menu://action`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum MenuAction {
+    Preferences,
+    Refresh,
+    /// Move to the export controls of the results.
+    Export,
+    ManageToken,
+    /// The listing cache was deleted; the next search asks the directory.
+    CacheCleared,
+    Shortcuts,
+    /// Show the details of a watched or shortlisted server.
+    OpenInstance {
+        domain: String,
+    },
+}
+
+const ACTION_EVENT: &str = "menu://action";
+
+// Prefixes of the ids of entries in the Watchlist, Shortlist and Recent
+// searches submenus
+const WATCH_PREFIX: &str = "watch:";
+const SHORTLIST_PREFIX: &str = "shortlist:";
+const RECENT_PREFIX: &str = "recent:";
+
+fn accelerator(key: &str) -> String {
+    if cfg!(target_os = "macos") {
+        format!("Cmd+{key}")
+    } else {
+        format!("Ctrl+{key}")
+    }
+}
+
+// Help is Cmd+? on macOS and F1 elsewhere
+fn help_accelerator() -> String {
+    if cfg!(target_os = "macos") {
+        "Cmd+Shift+/".into()
+    } else {
+        "F1".into()
+    }
+}
+
+fn item(app: &AppHandle, id: &str, text: String, accel: Option<String>) -> tauri::Result<MenuItem> {
+    MenuItem::with_id(app, id, text, true, accel)
+}
+
+// Disabled entry shown in a submenu with nothing to list
+fn placeholder(app: &AppHandle, id: &str, text: String) -> tauri::Result<MenuItem> {
+    MenuItem::with_id::<_, _, _, &str>(app, id, text, false, None)
+}
+
+/// Label of a recent search from its options, in the wizard's words.
+fn search_label(lang: &str, search: &RecentSearch) -> String {
+    let parts: Vec<String> = search
+        .query()
+        .iter()
+        .map(|(name, value)| match name.as_str() {
+            "lang" => value.to_uppercase(),
+            "signups" => t(lang, &format!("results.{value}")),
+            "software" => value.clone(),
+            "security" => t_with(lang, "wizard.security_min", &[("grade", value)]),
+            _ => t(lang, &format!("wizard.{name}_{value}")),
+        })
+        .collect();
+    if parts.is_empty() {
+        t(lang, "menu.recent_all")
+    } else {
+        parts.join(" · ")
+    }
+}
+
+/// Build the application menu (Tauri 2 API).
+pub fn build(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let lang = app.state::<UiLocale>().get();
+    let menu = Menu::new(app)?;
+
+    let file = Submenu::new(app, t(lang, "menu.file"), true)?;
+    file.append(&item(
+        app,
+        "export",
+        t(lang, "menu.export"),
+        Some(accelerator("E")),
+    )?)?;
+    file.append(&PredefinedMenuItem::separator(app)?)?;
+    file.append(&item(
+        app,
+        "token",
+        t(lang, "menu.token"),
+        Some(accelerator("Shift+T")),
+    )?)?;
+    file.append(&item(
+        app,
+        "clear-cache",
+        t(lang, "menu.clear_cache"),
+        Some(accelerator("Shift+Delete")),
+    )?)?;
+    file.append(&PredefinedMenuItem::separator(app)?)?;
+    file.append(&item(
+        app,
+        "preferences",
+        t(lang, "menu.preferences"),
+        Some(accelerator(",")),
+    )?)?;
+    file.append(&PredefinedMenuItem::separator(app)?)?;
+    // A simple Quit item (without platform-specific predefined helpers)
+    file.append(&item(
+        app,
+        "quit",
+        t(lang, "menu.quit"),
+        Some(accelerator("Q")),
+    )?)?;
+    menu.append(&file)?;
+
+    let view = Submenu::new(app, t(lang, "menu.view"), true)?;
+    view.append(&item(
+        app,
+        "refresh",
+        t(lang, "menu.refresh"),
+        Some(accelerator("R")),
+    )?)?;
+    view.append(&PredefinedMenuItem::separator(app)?)?;
+
+    let recent = Submenu::new(app, t(lang, "menu.recent"), true)?;
+    let searches = api::recent_store(app).list().unwrap_or_default();
+    if searches.is_empty() {
+        recent.append(&placeholder(
+            app,
+            "recent-empty",
+            t(lang, "menu.recent_empty"),
+        )?)?;
+    }
+    for search in &searches {
+        recent.append(&item(
+            app,
+            &format!("{RECENT_PREFIX}{}", search.link),
+            search_label(lang, search),
+            None,
+        )?)?;
+    }
+    if !searches.is_empty() {
+        recent.append(&PredefinedMenuItem::separator(app)?)?;
+        recent.append(&item(
+            app,
+            "recent-clear",
+            t(lang, "menu.recent_clear"),
+            None,
+        )?)?;
+    }
+    view.append(&recent)?;
+
+    let shortlist = Submenu::new(app, t(lang, "menu.shortlist"), true)?;
+    let favorites = api::favorites_store(app).list().unwrap_or_default();
+    if favorites.is_empty() {
+        shortlist.append(&placeholder(
+            app,
+            "shortlist-empty",
+            t(lang, "menu.shortlist_empty"),
+        )?)?;
+    }
+    for favorite in favorites {
+        shortlist.append(&item(
+            app,
+            &format!("{SHORTLIST_PREFIX}{}", favorite.domain),
+            favorite.domain.clone(),
+            None,
+        )?)?;
+    }
+    view.append(&shortlist)?;
+    menu.append(&view)?;
+
+    let watch = Submenu::new(app, t(lang, "menu.watchlist"), true)?;
+    let items = api::watchlist_store(app).list().unwrap_or_default();
+    if items.is_empty() {
+        watch.append(&placeholder(
+            app,
+            "watch-empty",
+            t(lang, "menu.watchlist_empty"),
+        )?)?;
+    }
+    for watched in items {
+        let label = match watched.signups.as_deref() {
+            Some(signups) => format!(
+                "{} ({})",
+                watched.domain,
+                t(lang, &format!("results.{signups}"))
+            ),
+            None => watched.domain.clone(),
+        };
+        watch.append(&item(
+            app,
+            &format!("{WATCH_PREFIX}{}", watched.domain),
+            label,
+            None,
+        )?)?;
+    }
+    menu.append(&watch)?;
+
+    let help = Submenu::new(app, t(lang, "menu.help"), true)?;
+    help.append(&item(
+        app,
+        "shortcuts",
+        t(lang, "menu.shortcuts"),
+        Some(help_accelerator()),
+    )?)?;
+    help.append(&PredefinedMenuItem::separator(app)?)?;
+    help.append(&item(app, "about", t(lang, "menu.about"), None)?)?;
+    menu.append(&help)?;
+
+    Ok(menu)
+}
+
+/// Replace the menu after the data it lists or its language has changed.
+pub fn rebuild(app: &AppHandle) {
+    if let Ok(menu) = build(app) {
+        let _ = app.set_menu(menu);
+    }
+}
+
+pub fn handle_event(app: &AppHandle, id: &str) {
+    if let Some(domain) = id
+        .strip_prefix(WATCH_PREFIX)
+        .or_else(|| id.strip_prefix(SHORTLIST_PREFIX))
+    {
+        emit_all(
+            app,
+            MenuAction::OpenInstance {
+                domain: domain.to_string(),
+            },
+        );
+        return;
+    }
+    if let Some(link) = id.strip_prefix(RECENT_PREFIX) {
+        // Checked again like any link: the file may have been edited
+        deep_link::open(app, link);
+        return;
+    }
+    match id {
+        "export" => emit_all(app, MenuAction::Export),
+        "token" => emit_all(app, MenuAction::ManageToken),
+        "clear-cache" => {
+            match directory::clear_cache(&backend::cache_path(&api::app_data_dir(app))) {
+                Ok(()) => emit_all(app, MenuAction::CacheCleared),
+                Err(e) => show_error(app, "menu.clear_cache_error", e),
+            }
+        }
+        "preferences" => emit_all(app, MenuAction::Preferences),
+        "refresh" => emit_all(app, MenuAction::Refresh),
+        "recent-clear" => {
+            let _ = api::recent_store(app).clear();
+            rebuild(app);
+        }
+        "shortcuts" => emit_all(app, MenuAction::Shortcuts),
+        "about" => show_about(app),
+        "quit" => {
+            // Graceful app exit; closing the window is what normally saves it
+            window_state::save(app);
+            app.exit(0);
+        }
+        _ => {}
+    }
+}
+
+/// How long ago the cached listing was fetched, in whole units.
+fn data_age(lang: &str, secs: u64) -> String {
+    let (key, count) = if secs < 3600 {
+        ("menu.age_minutes", secs / 60)
+    } else if secs < 86400 {
+        ("menu.age_hours", secs / 3600)
+    } else {
+        ("menu.age_days", secs / 86400)
+    };
+    t_with(lang, key, &[("count", &count.to_string())])
+}
+
+// Native dialog: it works before the webview has loaded, and screen readers
+// announce it like any system message
+fn show_about(app: &AppHandle) {
+    let lang = app.state::<UiLocale>().get();
+    let version = app.package_info().version.to_string();
+    let age = directory::cache_info(&backend::cache_path(&api::app_data_dir(app)))
+        .map(|info| data_age(lang, info.age_secs))
+        .unwrap_or_else(|| t(lang, "menu.about_no_data"));
+    let body = t_with(
+        lang,
+        "menu.about_body",
+        &[
+            ("version", &version),
+            ("source", &directory::api_base_url()),
+            ("age", &age),
+        ],
+    );
+    app.dialog()
+        .message(body)
+        .title(t(lang, "menu.about_title"))
+        .kind(MessageDialogKind::Info)
+        .show(|_| {});
+}
+
+fn show_error(app: &AppHandle, key: &str, error: impl Into<ApiError>) {
+    let locale = app.state::<UiLocale>();
+    let message = t_with(locale.get(), key, &[("error", &locale.error(error))]);
+    app.dialog()
+        .message(message)
+        .kind(MessageDialogKind::Error)
+        .show(|_| {});
+}
+
+fn emit_all(app: &AppHandle, action: MenuAction) {
+    for w in app.webview_windows().values() {
+        let _ = w.emit(ACTION_EVENT, action.clone());
+    }
+}
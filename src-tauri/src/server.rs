@@ -0,0 +1,550 @@
+//! Browser mode: a localhost HTTP server exposing the `api.rs` commands as
+//! JSON and serving the frontend, for systems where the webview is unusable.
+//!
+//! The server only listens on 127.0.0.1. Each run draws a random secret;
+//! the address printed at startup carries it once, and the browser then
+//! keeps it in a same-site cookie that every request must present.
+
+use std::{
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
+    process::Command,
+    thread,
+};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::backend::{self, ApiError, AppState};
+use crate::export::{self, ExportFormat};
+use crate::i18n::{self, localized, t, UiLocale};
+use instances_finder_core::accessibility;
+use instances_finder_core::activity;
+use instances_finder_core::batch::{self, DomainListSource};
+use instances_finder_core::directory;
+use instances_finder_core::error::Error;
+use instances_finder_core::model::{FetchParams, JsInstance};
+use instances_finder_core::moderation;
+use instances_finder_core::nodeinfo;
+use instances_finder_core::notices;
+use instances_finder_core::preview;
+use instances_finder_core::privacy;
+use instances_finder_core::probe::{self, ProbeClient};
+use instances_finder_core::score::Preferences;
+use instances_finder_core::security;
+use instances_finder_core::storage;
+
+const SESSION_COOKIE: &str = "instances_finder_session";
+const WORKERS: usize = 4;
+const MAX_BODY_BYTES: u64 = 8 * 1024 * 1024;
+// Tells the frontend to call `/api/<command>` instead of the Tauri IPC
+const BACKEND_META: &str = "<meta name=\"instances-finder-backend\" content=\"http\" />";
+const CSP: &str = "default-src 'self'; base-uri 'self'; form-action 'self'; object-src 'none'; \
+frame-ancestors 'none'; img-src 'self' data:; font-src 'self' data:; \
+style-src 'self' 'unsafe-inline'; script-src 'self'; connect-src 'self'";
+
+/// Frontend files by path, such as `/index.html`.
+pub type Assets<'a> = &'a (dyn Fn(&str) -> Option<Vec<u8>> + Sync);
+
+pub struct ServeOptions {
+    /// 0 picks a free port.
+    pub port: u16,
+    /// Open the address in the default browser.
+    pub open_browser: bool,
+}
+
+struct Context<'a> {
+    state: AppState,
+    data_dir: PathBuf,
+    secret: String,
+    port: u16,
+    assets: Assets<'a>,
+    locale: UiLocale,
+}
+
+type HttpResponse = Response<io::Cursor<Vec<u8>>>;
+
+/// Serve until the process is stopped.
+pub fn serve(options: &ServeOptions, assets: Assets) -> Result<(), String> {
+    let server = Server::http(("127.0.0.1", options.port)).map_err(|e| e.to_string())?;
+    let port = server
+        .server_addr()
+        .to_ip()
+        .map(|a| a.port())
+        .ok_or("not an IP listener")?;
+    let ctx = Context {
+        state: AppState::default(),
+        data_dir: storage::default_app_data_dir(),
+        secret: new_secret()?,
+        port,
+        assets,
+        locale: UiLocale::default(),
+    };
+    let url = format!("http://127.0.0.1:{port}/?secret={}", ctx.secret);
+    println!("Instances Finder is available at {url}");
+    println!("Keep this address private; press Ctrl+C to stop.");
+    if options.open_browser {
+        open_in_browser(&url);
+    }
+    thread::scope(|scope| {
+        for _ in 0..WORKERS {
+            scope.spawn(|| {
+                while let Ok(request) = server.recv() {
+                    handle(&ctx, request);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+fn new_secret() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+fn open_in_browser(url: &str) {
+    let spawned = if cfg!(target_os = "windows") {
+        Command::new("rundll32")
+            .args(["url.dll,FileProtocolHandler", url])
+            .spawn()
+    } else if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).spawn()
+    } else {
+        Command::new("xdg-open").arg(url).spawn()
+    };
+    if let Err(e) = spawned {
+        eprintln!("could not open a browser: {e}");
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).expect("valid header")
+}
+
+fn request_header<'r>(request: &'r Request, name: &'static str) -> Option<&'r str> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+// Compare without exiting early, so timing does not leak the secret
+fn same_secret(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+fn cookie_secret(request: &Request) -> Option<&str> {
+    request_header(request, "Cookie")?
+        .split(';')
+        .filter_map(|c| c.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value)
+}
+
+fn query_secret(url: &str) -> Option<&str> {
+    url.split_once('?')?
+        .1
+        .split('&')
+        .find_map(|p| p.strip_prefix("secret="))
+}
+
+fn with_security_headers(response: HttpResponse) -> HttpResponse {
+    response
+        .with_header(header("Content-Security-Policy", CSP))
+        .with_header(header("X-Content-Type-Options", "nosniff"))
+        .with_header(header("Referrer-Policy", "no-referrer"))
+}
+
+fn text_response(status: u16, body: &str) -> HttpResponse {
+    Response::from_data(body.as_bytes().to_vec())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "text/plain; charset=utf-8"))
+}
+
+fn json_response(status: u16, body: &Value) -> HttpResponse {
+    Response::from_data(body.to_string().into_bytes())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+        .with_header(header("Cache-Control", "no-store"))
+}
+
+fn handle(ctx: &Context, mut request: Request) {
+    let response = route(ctx, &mut request);
+    let _ = request.respond(with_security_headers(response));
+}
+
+fn route(ctx: &Context, request: &mut Request) -> HttpResponse {
+    let lang = ctx.locale.get();
+    // A page on another site must not reach us through a rebound DNS name
+    let host = request_header(request, "Host").unwrap_or_default();
+    let port = ctx.port;
+    if host != format!("127.0.0.1:{port}") && host != format!("localhost:{port}") {
+        return text_response(403, &t(lang, "server.forbidden"));
+    }
+
+    let url = request.url().to_string();
+    if let Some(secret) = query_secret(&url) {
+        if !same_secret(secret, &ctx.secret) {
+            return text_response(403, &t(lang, "server.invalid_secret"));
+        }
+        let cookie = format!(
+            "{SESSION_COOKIE}={}; Path=/; HttpOnly; SameSite=Strict",
+            ctx.secret
+        );
+        return Response::from_data(Vec::new())
+            .with_status_code(303)
+            .with_header(header("Set-Cookie", &cookie))
+            .with_header(header("Location", "/"));
+    }
+    if !cookie_secret(request).is_some_and(|s| same_secret(s, &ctx.secret)) {
+        return text_response(403, &t(lang, "server.no_session"));
+    }
+
+    let path = url.split(['?', '#']).next().unwrap_or("/").to_string();
+    match (request.method(), path.strip_prefix("/api/")) {
+        (Method::Post, Some(command)) => api_request(ctx, request, command),
+        (_, Some(_)) => text_response(405, &t(lang, "server.method_not_allowed")),
+        (Method::Get | Method::Head, None) => static_file(ctx, &path),
+        _ => text_response(405, &t(lang, "server.method_not_allowed")),
+    }
+}
+
+fn api_request(ctx: &Context, request: &mut Request, command: &str) -> HttpResponse {
+    let lang = ctx.locale.get();
+    // Rules out plain HTML forms, which cannot set this type
+    let is_json =
+        request_header(request, "Content-Type").is_some_and(|t| t.starts_with("application/json"));
+    if !is_json {
+        return text_response(415, &t(lang, "server.expected_json"));
+    }
+    let mut body = Vec::new();
+    let read = request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_end(&mut body);
+    if read.is_err() {
+        return text_response(400, &t(lang, "server.unreadable_body"));
+    }
+    let args = if body.is_empty() {
+        Ok(Value::Object(Map::new()))
+    } else {
+        serde_json::from_slice(&body).map_err(ApiError::from)
+    };
+    match args.and_then(|args| dispatch(ctx, command, &args)) {
+        Ok(value) => json_response(200, &value),
+        Err(e) => json_response(400, &json!({ "error": ctx.locale.error(e) })),
+    }
+}
+
+fn content_type(path: &str) -> &'static str {
+    match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js" | "mjs") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ico") => "image/x-icon",
+        Some("woff2") => "font/woff2",
+        Some("txt") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+fn static_file(ctx: &Context, path: &str) -> HttpResponse {
+    let not_found = || text_response(404, &t(ctx.locale.get(), "server.not_found"));
+    if asset_path(path).is_none() {
+        return not_found();
+    }
+    let path = if path == "/" { "/index.html" } else { path };
+    let (path, bytes) = match (ctx.assets)(path) {
+        Some(bytes) => (path, bytes),
+        // Unknown routes belong to the single-page app
+        None if Path::new(path).extension().is_none() => match (ctx.assets)("/index.html") {
+            Some(bytes) => ("/index.html", bytes),
+            None => return not_found(),
+        },
+        None => return not_found(),
+    };
+    let bytes = if path == "/index.html" {
+        String::from_utf8_lossy(&bytes)
+            .replacen("<head>", &format!("<head>\n    {BACKEND_META}"), 1)
+            .into_bytes()
+    } else {
+        bytes
+    };
+    Response::from_data(bytes).with_header(header("Content-Type", content_type(path)))
+}
+
+/// Frontend files from a built `dist/` directory.
+pub fn dir_assets(dir: &Path, path: &str) -> Option<Vec<u8>> {
+    let root = dir.canonicalize().ok()?;
+    let file = root.join(asset_path(path)?).canonicalize().ok()?;
+    // A symbolic link may still lead out of the directory
+    if !file.starts_with(&root) {
+        return None;
+    }
+    std::fs::read(file).ok()
+}
+
+// The URL path as a relative file path, if it only names plain entries:
+// no `..`, `.`, root or drive prefix
+fn asset_path(path: &str) -> Option<&Path> {
+    let relative = Path::new(path.trim_start_matches('/'));
+    relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+        .then_some(relative)
+}
+
+// Tauri maps camelCase JS keys to snake_case arguments; accept both.
+fn arg<T: DeserializeOwned>(args: &Value, name: &str) -> Result<T, ApiError> {
+    let camel: String = name
+        .split('_')
+        .enumerate()
+        .map(|(i, part)| {
+            let mut chars = part.chars();
+            match (i, chars.next()) {
+                (0, _) | (_, None) => part.to_string(),
+                (_, Some(first)) => first.to_uppercase().chain(chars).collect(),
+            }
+        })
+        .collect();
+    let value = args
+        .get(name)
+        .or_else(|| args.get(&camel))
+        .cloned()
+        .unwrap_or(Value::Null);
+    serde_json::from_value(value).map_err(|e| ApiError::InvalidArgument {
+        name: name.to_string(),
+        error: e.to_string(),
+    })
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, ApiError> {
+    Ok(serde_json::to_value(value)?)
+}
+
+fn with_client<T: Serialize>(
+    args: &Value,
+    f: impl FnOnce(&ProbeClient) -> Result<T, Error>,
+) -> Result<Value, ApiError> {
+    let domain: String = arg(args, "domain")?;
+    to_value(f(&ProbeClient::new(&domain)?)?)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Download {
+    file_name: String,
+    content: String,
+}
+
+/// Run `command` as the Tauri command of the same name would. Native file
+/// dialogs have no equivalent here, so commands that would open one need
+/// an explicit path.
+fn dispatch(ctx: &Context, command: &str, args: &Value) -> Result<Value, ApiError> {
+    let dir = ctx.data_dir.as_path();
+    let state = &ctx.state;
+    let lang = ctx.locale.get();
+    match command {
+        "token_status" => to_value(state.token_status()),
+        "save_token" => to_value(state.save_token(arg(args, "token")?, arg(args, "persist")?)?),
+        "clear_token" => {
+            state.clear_token();
+            Ok(Value::Null)
+        }
+        "test_token" => {
+            let token = match arg::<Option<String>>(args, "token")? {
+                Some(t) => t,
+                None => state.token()?,
+            };
+            to_value(directory::test_token(&token)?)
+        }
+        "clear_instances_cache" => to_value(directory::clear_cache(&backend::cache_path(dir))?),
+        "fetch_instances" => {
+            let params: FetchParams = arg(args, "params")?;
+            let bypass: Option<bool> = arg(args, "bypass_cache")?;
+            to_value(backend::fetch_instances(
+                dir,
+                state,
+                &params,
+                bypass.unwrap_or(false),
+                lang,
+            )?)
+        }
+        "fetch_languages" => to_value(directory::languages(&state.token()?)?),
+        "fetch_moderation" => with_client(args, moderation::moderation_report),
+        "fetch_accessibility" => with_client(args, accessibility::accessibility_report),
+        "fetch_nodeinfo" => with_client(args, nodeinfo::fetch_software),
+        "fetch_notice" => with_client(args, notices::detect_notice),
+        "check_notices" => to_value(backend::check_notices(&arg::<Vec<String>>(
+            args, "domains",
+        )?)),
+        "preview_instance" => {
+            let labels = i18n::preview_labels(lang);
+            with_client(args, |client| preview::preview(client, &labels))
+        }
+        "fetch_activity" => {
+            let users: Option<u64> = arg(args, "users")?;
+            with_client(args, |client| activity::activity_report(client, users))
+        }
+        "audit_security" => with_client(args, security::audit),
+        "audit_privacy" => with_client(args, privacy::privacy_summary),
+        "normalize_domain" => to_value(probe::normalize_domain(&arg::<String>(args, "domain")?)?),
+        "evaluate_domain" => to_value(backend::evaluate_domain(
+            dir,
+            &arg::<String>(args, "domain")?,
+            lang,
+        )?),
+        "evaluate_domain_list" => {
+            let text = match arg::<DomainListSource>(args, "source")? {
+                DomainListSource::Text { text } => text,
+                DomainListSource::File { path: Some(path) } => {
+                    batch::read_domain_file(Path::new(&path))?
+                }
+                DomainListSource::File { path: None } => return Err(ApiError::BrowserNoDialogList),
+            };
+            let prefs: Option<Preferences> = arg(args, "prefs")?;
+            to_value(backend::evaluate_domain_text(
+                dir,
+                &text,
+                &prefs.unwrap_or_default(),
+                lang,
+                &|_| {},
+            ))
+        }
+        "advisories_info" => to_value(backend::advisories_info(dir)),
+        "import_advisories" => {
+            let path: String =
+                arg::<Option<String>>(args, "path")?.ok_or(ApiError::BrowserNoDialogFile)?;
+            to_value(Some(backend::import_advisories(dir, Path::new(&path))?))
+        }
+        "check_uptime" => to_value(backend::check_uptime(
+            dir,
+            &arg::<Vec<String>>(args, "domains")?,
+        )),
+        "uptime_stats" => to_value(backend::uptime_stats(
+            dir,
+            &arg::<Vec<String>>(args, "domains")?,
+        )),
+        "purge_uptime_history" => {
+            let domain: Option<String> = arg(args, "domain")?;
+            to_value(backend::purge_uptime_history(dir, domain.as_deref())?)
+        }
+        "watchlist_list" => to_value(backend::watchlist_store(dir).list()?),
+        "watchlist_add" => {
+            to_value(backend::watchlist_store(dir).add(&arg::<String>(args, "domain")?)?)
+        }
+        "watchlist_remove" => {
+            to_value(backend::watchlist_store(dir).remove(&arg::<String>(args, "domain")?)?)
+        }
+        "watchlist_check" => {
+            let store = backend::watchlist_store(dir);
+            store.check_all()?;
+            to_value(store.list()?)
+        }
+        "favorites_list" => {
+            let refresh: Option<bool> = arg(args, "refresh")?;
+            to_value(backend::favorites_list(
+                dir,
+                refresh.unwrap_or(false),
+                lang,
+            )?)
+        }
+        "favorites_add" => {
+            let instance: JsInstance = arg(args, "instance")?;
+            let items = backend::favorites_store(dir).add(instance, arg(args, "note")?)?;
+            to_value(localized(items, lang))
+        }
+        "favorites_remove" => {
+            let items = backend::favorites_store(dir).remove(&arg::<String>(args, "domain")?)?;
+            to_value(localized(items, lang))
+        }
+        "favorites_reorder" => {
+            let domains: Vec<String> = arg(args, "domains")?;
+            to_value(localized(
+                backend::favorites_store(dir).reorder(&domains)?,
+                lang,
+            ))
+        }
+        "favorites_set_note" => {
+            let domain: String = arg(args, "domain")?;
+            let note: String = arg(args, "note")?;
+            to_value(localized(
+                backend::favorites_store(dir).set_note(&domain, &note)?,
+                lang,
+            ))
+        }
+        // The browser saves the file itself
+        "export_results" => {
+            let format: ExportFormat = arg(args, "format")?;
+            let (source, items) = backend::export_items(dir, arg(args, "items")?, lang)?;
+            to_value(Download {
+                file_name: export::file_name(source, format),
+                content: export::render(&items, source, format, lang),
+            })
+        }
+        // Errors and labels of later commands follow the page's language
+        "set_locale" => {
+            ctx.locale.set(&arg::<String>(args, "lang")?);
+            Ok(Value::Null)
+        }
+        _ => Err(ApiError::UnknownCommand {
+            command: command.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn asset_paths_stay_relative() {
+        assert_eq!(
+            asset_path("/assets/app.js"),
+            Some(Path::new("assets/app.js"))
+        );
+        for path in [
+            "/../secret",
+            "/assets/../../secret",
+            "/./index.html",
+            "/assets/..",
+        ] {
+            assert_eq!(asset_path(path), None, "{path}");
+        }
+    }
+
+    #[test]
+    fn dir_assets_stay_in_the_directory() {
+        let base = std::env::temp_dir().join(format!("dir-assets-{}", std::process::id()));
+        let dist = base.join("dist");
+        fs::create_dir_all(&dist).unwrap();
+        fs::write(dist.join("index.html"), "app").unwrap();
+        fs::write(base.join("secret.txt"), "secret").unwrap();
+
+        assert_eq!(
+            dir_assets(&dist, "/index.html").as_deref(),
+            Some(&b"app"[..])
+        );
+        assert_eq!(dir_assets(&dist, "/../secret.txt"), None);
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(base.join("secret.txt"), dist.join("link.txt")).unwrap();
+            assert_eq!(dir_assets(&dist, "/link.txt"), None);
+        }
+        let _ = fs::remove_dir_all(base);
+    }
+}
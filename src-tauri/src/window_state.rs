@@ -0,0 +1,268 @@
+//! Size, position, maximized state and monitor of the main window, followed
+//! as it changes, saved when it closes or the app quits and restored at
+//! startup. A saved place is only reused if it is still visible with the
+//! monitors connected now.
+
+use std::{fs, path::PathBuf, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewWindow, Window};
+
+use instances_finder_core::storage;
+
+const MIN_WIDTH: u32 = 480;
+const MIN_HEIGHT: u32 = 360;
+// Part of the window that must stay on screen to be grabbed and moved
+const MIN_VISIBLE: i64 = 120;
+const TITLEBAR_HEIGHT: i64 = 40;
+
+/// Outer geometry (frame included) in physical pixels, as saved in
+/// `window_state.json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+    /// Name of the monitor the window was on, when the system gives one.
+    pub monitor: Option<String>,
+}
+
+/// Last known state, kept across move and resize events.
+#[derive(Debug, Default)]
+pub struct TrackedState(Mutex<Option<WindowState>>);
+
+struct Area {
+    name: Option<String>,
+    x: i64,
+    y: i64,
+    width: i64,
+    height: i64,
+}
+
+impl Area {
+    fn of(monitor: &Monitor) -> Self {
+        let (pos, size) = (monitor.position(), monitor.size());
+        Area {
+            name: monitor.name().cloned(),
+            x: pos.x.into(),
+            y: pos.y.into(),
+            width: size.width.into(),
+            height: size.height.into(),
+        }
+    }
+
+    // Enough of the title bar is on this monitor to move the window
+    fn shows_titlebar(&self, s: &WindowState) -> bool {
+        let (x, y, w) = (i64::from(s.x), i64::from(s.y), i64::from(s.width));
+        let visible = (x + w).min(self.x + self.width) - x.max(self.x);
+        let titlebar = (y + TITLEBAR_HEIGHT).min(self.y + self.height) - y.max(self.y);
+        visible >= MIN_VISIBLE.min(w) && titlebar > 0
+    }
+}
+
+fn path(app: &AppHandle) -> PathBuf {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .unwrap_or_else(|_| std::env::temp_dir());
+    storage::data_file(&dir, storage::WINDOW_STATE_FILE)
+}
+
+fn load(app: &AppHandle) -> Option<WindowState> {
+    let text = fs::read_to_string(path(app)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// Where to put a window saved as `saved` given the monitors now, or
+/// `None` to leave it centered by the system. The saved monitor is
+/// preferred; a place that is no longer visible is moved to the center of
+/// the monitor, and a window larger than the monitor is reduced and moved
+/// onto it.
+fn placement(saved: &WindowState, monitors: &[Area]) -> Option<WindowState> {
+    let by_name = saved
+        .monitor
+        .as_ref()
+        .and_then(|name| monitors.iter().find(|m| m.name.as_ref() == Some(name)));
+    let area = by_name
+        .filter(|m| m.shows_titlebar(saved))
+        .or_else(|| monitors.iter().find(|m| m.shows_titlebar(saved)))
+        .or(by_name)
+        .or(monitors.first())?;
+
+    let clamp = |value: u32, min: u32, max: i64| i64::from(value.max(min)).min(max).max(1) as u32;
+    let width = clamp(saved.width, MIN_WIDTH, area.width);
+    let height = clamp(saved.height, MIN_HEIGHT, area.height);
+    let mut placed = WindowState {
+        width,
+        height,
+        monitor: area.name.clone(),
+        ..saved.clone()
+    };
+    if width < saved.width || height < saved.height {
+        let fit = |pos: i32, start: i64, room: i64, size: u32| {
+            i64::from(pos)
+                .min(start + room - i64::from(size))
+                .max(start) as i32
+        };
+        placed.x = fit(saved.x, area.x, area.width, width);
+        placed.y = fit(saved.y, area.y, area.height, height);
+    }
+    if !area.shows_titlebar(&placed) {
+        placed.x = (area.x + (area.width - i64::from(width)) / 2) as i32;
+        placed.y = (area.y + (area.height - i64::from(height)) / 2) as i32;
+    }
+    Some(placed)
+}
+
+/// Put the main window back where it was, then show it (it starts hidden
+/// so the restore does not flash).
+pub fn restore(window: &WebviewWindow) {
+    let app = window.app_handle();
+    if let Some(saved) = load(app) {
+        let monitors: Vec<Area> = window
+            .available_monitors()
+            .unwrap_or_default()
+            .iter()
+            .map(Area::of)
+            .collect();
+        if let Some(placed) = placement(&saved, &monitors) {
+            // `set_size` takes the inner size: leave room for the frame
+            let (frame_width, frame_height) = match (window.outer_size(), window.inner_size()) {
+                (Ok(outer), Ok(inner)) => (
+                    outer.width.saturating_sub(inner.width),
+                    outer.height.saturating_sub(inner.height),
+                ),
+                _ => (0, 0),
+            };
+            let _ = window.set_size(PhysicalSize::new(
+                placed.width.saturating_sub(frame_width).max(1),
+                placed.height.saturating_sub(frame_height).max(1),
+            ));
+            let _ = window.set_position(PhysicalPosition::new(placed.x, placed.y));
+            if placed.maximized {
+                let _ = window.maximize();
+            }
+            *app.state::<TrackedState>().0.lock().unwrap() = Some(placed);
+        }
+    }
+    let _ = window.show();
+}
+
+/// Follow moves and resizes of `window`. While it is maximized or
+/// minimized, the last normal geometry is kept for the next restore.
+pub fn track(window: &Window) {
+    if window.label() != "main" {
+        return;
+    }
+    let tracked = window.state::<TrackedState>();
+    let mut state = tracked.0.lock().unwrap();
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    let current = state.get_or_insert_with(WindowState::default);
+    current.maximized = maximized;
+    if maximized {
+        return;
+    }
+    if let (Ok(pos), Ok(size)) = (window.outer_position(), window.outer_size()) {
+        current.x = pos.x;
+        current.y = pos.y;
+        current.width = size.width;
+        current.height = size.height;
+    }
+    if let Ok(Some(monitor)) = window.current_monitor() {
+        current.monitor = monitor.name().cloned();
+    }
+}
+
+/// Write the tracked state to disk.
+pub fn save(app: &AppHandle) {
+    let state = app.state::<TrackedState>().0.lock().unwrap().clone();
+    if let Some(state) = state {
+        if let Ok(text) = serde_json::to_string_pretty(&state) {
+            let _ = fs::write(path(app), text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(name: &str, x: i64, y: i64, width: i64, height: i64) -> Area {
+        Area {
+            name: Some(name.into()),
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn saved(monitor: &str, x: i32, y: i32, width: u32, height: u32) -> WindowState {
+        WindowState {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+            monitor: Some(monitor.into()),
+        }
+    }
+
+    #[test]
+    fn keeps_a_visible_place() {
+        let monitors = [
+            area("eDP-1", 0, 0, 1920, 1080),
+            area("HDMI-1", 1920, 0, 2560, 1440),
+        ];
+        let state = saved("HDMI-1", 2100, 100, 1200, 800);
+        assert_eq!(placement(&state, &monitors), Some(state));
+    }
+
+    #[test]
+    fn moves_to_another_monitor_when_one_is_removed() {
+        let monitors = [area("eDP-1", 0, 0, 1920, 1080)];
+        let placed = placement(&saved("HDMI-1", 2100, 100, 1200, 800), &monitors).unwrap();
+        assert_eq!(placed.monitor.as_deref(), Some("eDP-1"));
+        assert_eq!((placed.x, placed.y), (360, 140));
+        assert_eq!((placed.width, placed.height), (1200, 800));
+    }
+
+    #[test]
+    fn keeps_a_partly_off_screen_window_that_can_be_grabbed() {
+        let monitors = [area("eDP-1", 0, 0, 1920, 1080)];
+        let state = saved("eDP-1", -600, 200, 800, 600);
+        assert_eq!(placement(&state, &monitors), Some(state));
+
+        // Only 70 pixels of the title bar, or none of it, left on screen
+        for (x, y) in [(1850, 200), (200, -300), (200, 1080)] {
+            let placed = placement(&saved("eDP-1", x, y, 800, 600), &monitors).unwrap();
+            assert_eq!((placed.x, placed.y), (560, 240), "from {x},{y}");
+        }
+    }
+
+    #[test]
+    fn fits_a_window_saved_at_another_scale_factor() {
+        // Saved on a 4K monitor at 200 %, which is now a 1080p one at 100 %
+        let monitors = [area("DP-1", 0, 0, 1920, 1080)];
+        let placed = placement(&saved("DP-1", 400, 200, 3000, 1800), &monitors).unwrap();
+        assert_eq!((placed.width, placed.height), (1920, 1080));
+        assert_eq!((placed.x, placed.y), (0, 0));
+
+        // Saved at 100 %, restored on a scaled monitor with a negative origin
+        let monitors = [area("DP-1", -3840, 0, 3840, 2160)];
+        let placed = placement(&saved("DP-1", 100, 100, 200, 100), &monitors).unwrap();
+        assert_eq!((placed.width, placed.height), (MIN_WIDTH, MIN_HEIGHT));
+        assert_eq!((placed.x, placed.y), (-2160, 900));
+    }
+
+    #[test]
+    fn leaves_placement_to_the_system_without_monitors() {
+        assert_eq!(placement(&saved("eDP-1", 0, 0, 800, 600), &[]), None);
+    }
+}
@@ -8,10 +8,64 @@ import { useI18n } from './i18n';
 import type { Instance, Preferences } from './types';
 import { rankInstances } from './lib/score';
 import { TokenSetup } from './components/TokenSetup';
-import { fetchInstances, clearInstancesCache, fetchLanguages } from './lib/api';
+import {
+  checkNotices,
+  favoritesAdd,
+  favoritesList,
+  favoritesRemove,
+  fetchInstances,
+  clearInstancesCache,
+  fetchLanguages,
+  isBrowserServer,
+  onDeepLink,
+  onMenuAction,
+  setLocale,
+  takeDeepLink,
+  onWatchlistChanged,
+  watchlistAdd,
+  watchlistList,
+  watchlistRemove,
+} from './lib/api';
+import type { FavoriteView, JsInstance, MenuAction, ServiceNotice } from './lib/api';
 import { AppShell } from './components/AppShell';
+import { EvaluateDomain } from './components/EvaluateDomain';
+import { InstancePreview } from './components/InstancePreview';
+import { Shortlist } from './components/Shortlist';
+import { ExportControls } from './components/ExportControls';
+
+function toInstance(it: JsInstance): Instance {
+  const reg =
+    it.region === 'eu' || it.region === 'na' || it.region === 'other' ? it.region : 'other';
+  const sz = it.size as 1 | 2 | 3;
+  return {
+    domain: it.domain,
+    description: it.description,
+    languages: Array.isArray(it.languages) ? it.languages.map((l) => l.toLowerCase()) : [],
+    signups: it.signups,
+    size: sz,
+    sizeLabel: it.sizeLabel,
+    region: reg,
+    availability: it.availability,
+    moderation: it.moderation,
+    accessibility: it.accessibility,
+    software: it.software,
+    security: it.security,
+    privacy: it.privacy,
+    vulnerability: it.vulnerability,
+    notice: it.notice,
+    users: it.users,
+    activeMonth: it.activeMonth,
+    activity: it.activity,
+  };
+}
+
+// Notices are checked on this many top results even without the full check
+// (named in prefs.check_notices_help)
+const NOTICE_TOP_RESULTS = 10;
 
 const isTauri = () => typeof window !== 'undefined' && '__TAURI_IPC__' in window;
+// Desktop app or `--serve`: the backend commands are reachable
+const hasBackend = () => isTauri() || isBrowserServer();
 
 const App: React.FC = () => {
   const { t, lang, setLang } = useI18n();
@@ -22,6 +76,11 @@ const App: React.FC = () => {
     signups: 'any',
     region: 'any',
     nsfw: 'any',
+    software: 'any',
+    security: 'any',
+    privacy: 'any',
+    vulnerable: 'any',
+    sort: 'relevance',
   });
   const [results, setResults] = useState<Instance[]>([]);
   const [status, setStatus] = useState<'idle' | 'loading' | 'done' | 'error' | 'needs_token'>(
@@ -32,9 +91,19 @@ const App: React.FC = () => {
   const [errorLive, setErrorLive] = useState<string>('');
   const [expert, setExpert] = useState<boolean>(false);
   const [brailleRefresh, setBrailleRefresh] = useState<boolean>(false);
+  // Contacts every listed server, so off unless asked for; the top results
+  // are checked anyway
+  const [checkAllNotices, setCheckAllNotices] = useState<boolean>(false);
   const [refreshTick, setRefreshTick] = useState<number>(0);
   const [flash, setFlash] = useState<string | null>(null);
   const [prefsOpen, setPrefsOpen] = useState<boolean>(false);
+  const [shortcutsOpen, setShortcutsOpen] = useState<boolean>(false);
+  const [tokenManage, setTokenManage] = useState<boolean>(false);
+  const [previewDomain, setPreviewDomain] = useState<string | null>(null);
+  const [watched, setWatched] = useState<string[]>([]);
+  const [favorites, setFavorites] = useState<FavoriteView[]>([]);
+  // Backend records as received, for snapshots in the shortlist
+  const rawRef = useRef(new Map<string, JsInstance>());
   const [availableLangs, setAvailableLangs] = useState<string[]>(['fr', 'en']);
   const liveRef = useRef<HTMLDivElement | null>(null);
   const appRef = useRef<HTMLDivElement | null>(null);
@@ -53,32 +122,27 @@ const App: React.FC = () => {
       try {
         const items = await fetchInstances(
           {
-            language: prefs.languages.length ? prefs.languages[0] : undefined,
+            language: prefs.languages.length ? prefs.languages.join(',') : undefined,
             include_closed: false,
             include_down: false,
             max: 200,
             signups: prefs.signups === 'any' ? undefined : (prefs.signups as 'open' | 'approval'),
             region: expert && prefs.region !== 'any' ? prefs.region : undefined,
             size: prefs.size === 'any' ? undefined : prefs.size,
+            software: expert && prefs.software !== 'any' ? prefs.software : undefined,
+            min_security: expert && prefs.security !== 'any' ? prefs.security : undefined,
+            exclude_cdn: expert && (prefs.privacy === 'no_cdn' || prefs.privacy === 'strict'),
+            exclude_third_party:
+              expert && (prefs.privacy === 'no_third_party' || prefs.privacy === 'strict'),
+            hide_vulnerable: expert && prefs.vulnerable === 'hide',
+            check_notices: checkAllNotices,
+            check_activity: expert,
           },
           import.meta.env.DEV || refreshTick > 0
         );
         if (cancelled) return;
-        const normalized: Instance[] = items.map((it) => {
-          const reg =
-            it.region === 'eu' || it.region === 'na' || it.region === 'other' ? it.region : 'other';
-          const sz = it.size as 1 | 2 | 3;
-          return {
-            domain: it.domain,
-            description: it.description,
-            languages: Array.isArray(it.languages) ? it.languages.map((l) => l.toLowerCase()) : [],
-            signups: it.signups,
-            size: sz,
-            sizeLabel: it.sizeLabel,
-            region: reg,
-            availability: it.availability,
-          };
-        });
+        items.forEach((it) => rawRef.current.set(it.domain, it));
+        const normalized: Instance[] = items.map(toInstance);
         const ranked = rankInstances(normalized, prefs);
         setResults(ranked);
         setStatus('done');
@@ -93,6 +157,22 @@ const App: React.FC = () => {
         setTimeout(() => {
           resultsListRef.current?.focus();
         }, 0);
+        if (!checkAllNotices) {
+          const top = ranked.slice(0, NOTICE_TOP_RESULTS).map((r) => r.domain);
+          const notices: Record<string, ServiceNotice> = await checkNotices(top).catch(() => ({}));
+          if (cancelled || Object.keys(notices).length === 0) return;
+          Object.entries(notices).forEach(([domain, notice]) => {
+            const raw = rawRef.current.get(domain);
+            if (raw) rawRef.current.set(domain, { ...raw, notice });
+          });
+          // Flagged servers move down, like with the full check
+          setResults((prev) =>
+            rankInstances(
+              prev.map((r) => (notices[r.domain] ? { ...r, notice: notices[r.domain] } : r)),
+              prefs
+            )
+          );
+        }
       } catch (_e) {
         if (!cancelled) {
           setErrorMsg(t('status.error'));
@@ -104,7 +184,7 @@ const App: React.FC = () => {
     return () => {
       cancelled = true;
     };
-  }, [prefs, tokenReady, expert, t, refreshTick]);
+  }, [prefs, tokenReady, expert, checkAllNotices, t, refreshTick]);
 
   // Fetch all available languages once token is ready
   useEffect(() => {
@@ -121,6 +201,16 @@ const App: React.FC = () => {
 
   const onApply = (p: Preferences) => setPrefs(p);
 
+  // Servers evaluated on demand join the list and are ranked like directory results
+  const onEvaluated = (items: JsInstance[]) => {
+    items.forEach((it) => rawRef.current.set(it.domain, it));
+    const added = items.map(toInstance);
+    const domains = new Set(added.map((inst) => inst.domain));
+    setResults((prev) =>
+      rankInstances([...prev.filter((r) => !domains.has(r.domain)), ...added], prefs)
+    );
+  };
+
   const statusText = useMemo(() => {
     if (status === 'loading') return t('status.loading');
     if (status === 'done') return t('status.done', { count: results.length });
@@ -137,20 +227,62 @@ const App: React.FC = () => {
     }
   }, [status, errorMsg, t]);
 
-  // React to native "Preferences" menu: open modal preferences.
+  // Keep the backend (native menu, labels, errors) in the interface language
+  useEffect(() => {
+    if (!hasBackend()) return;
+    setLocale(lang).catch(() => {
+      /* no-op: the backend keeps its previous language */
+    });
+  }, [lang]);
+
+  // Native menu: each item sends one typed action
   useEffect(() => {
     if (!isTauri()) return;
     let unlisten: (() => void) | undefined;
+    const announce = (detail: string) =>
+      window.dispatchEvent(new CustomEvent('app:flash', { detail }));
+    const onAction = async (a: MenuAction) => {
+      switch (a.action) {
+        case 'preferences':
+          setShortcutsOpen(false);
+          setPrefsOpen(true);
+          break;
+        case 'shortcuts':
+          setShortcutsOpen(true);
+          setPrefsOpen(true);
+          break;
+        case 'refresh':
+          try {
+            await clearInstancesCache();
+          } catch (_) {
+            /* no-op: cache clear failure is non-blocking */
+          }
+          setRefreshTick((n) => n + 1);
+          break;
+        case 'export': {
+          const format = document.getElementById('export-results-format');
+          format?.scrollIntoView({ block: 'center' });
+          format?.focus();
+          break;
+        }
+        case 'manage_token':
+          setTokenManage(true);
+          break;
+        case 'cache_cleared':
+          announce(t('menu.cache_cleared'));
+          break;
+        case 'open_instance':
+          setPreviewDomain(a.domain);
+          break;
+      }
+    };
     (async () => {
-      const { listen } = await import('@tauri-apps/api/event');
-      unlisten = await listen('menu://preferences', () => {
-        setPrefsOpen(true);
-      });
+      unlisten = await onMenuAction((a) => void onAction(a));
     })();
     return () => {
       if (unlisten) unlisten();
     };
-  }, []);
+  }, [t]);
 
   // Mirror status and flash into a live region for Orca
   useEffect(() => {
@@ -163,26 +295,112 @@ const App: React.FC = () => {
     if (flash) liveRef.current.textContent = flash;
   }, [flash]);
 
-  // React to native Refresh
+  // Watchlist: load it and follow registration changes
+  useEffect(() => {
+    if (!hasBackend()) return;
+    const unlisteners: (() => void)[] = [];
+    (async () => {
+      try {
+        setWatched((await watchlistList()).map((w) => w.domain));
+      } catch (_) {
+        /* no-op: the watchlist is optional */
+      }
+      unlisteners.push(
+        await onWatchlistChanged((changes) => {
+          for (const c of changes) {
+            window.dispatchEvent(
+              new CustomEvent('app:flash', {
+                detail: t('watchlist.changed', {
+                  domain: c.domain,
+                  status: t(`results.${c.to}`),
+                }),
+              })
+            );
+          }
+        })
+      );
+    })();
+    return () => unlisteners.forEach((u) => u());
+  }, [t]);
+
+  // instances-finder:// links: apply a search or open an instance
   useEffect(() => {
     if (!isTauri()) return;
     let unlisten: (() => void) | undefined;
+    const announce = (detail: string) =>
+      window.dispatchEvent(new CustomEvent('app:flash', { detail }));
+    const apply = async () => {
+      const link = await takeDeepLink().catch(() => null);
+      if (!link) return;
+      if (link.kind === 'instance') {
+        setPreviewDomain(link.domain);
+        return;
+      }
+      const p = link.preferences;
+      // Region, software, security, privacy and advisories are expert filters
+      const expertOnly =
+        p.region !== 'any' ||
+        p.software !== 'any' ||
+        p.security !== 'any' ||
+        p.privacy !== 'any' ||
+        p.vulnerable !== 'any';
+      if (expertOnly) setExpert(true);
+      setPrefs(p);
+      announce(t('deeplink.applied'));
+    };
     (async () => {
-      const { listen } = await import('@tauri-apps/api/event');
-      unlisten = await listen('menu://refresh', async () => {
-        try {
-          await clearInstancesCache();
-        } catch (_) {
-          /* no-op: cache clear failure is non-blocking */
-        }
-        setRefreshTick((n) => n + 1);
-      });
+      unlisten = await onDeepLink(apply, (error) => announce(t('deeplink.invalid', { error })));
+      await apply();
     })();
     return () => {
       if (unlisten) unlisten();
     };
+  }, [t]);
+
+  const onToggleWatch = async (domain: string) => {
+    try {
+      const items = watched.includes(domain)
+        ? await watchlistRemove(domain)
+        : await watchlistAdd(domain);
+      setWatched(items.map((w) => w.domain));
+      window.dispatchEvent(
+        new CustomEvent('app:flash', {
+          detail: t(watched.includes(domain) ? 'watchlist.removed' : 'watchlist.added', {
+            domain,
+          }),
+        })
+      );
+    } catch (_) {
+      /* no-op: keep the previous list */
+    }
+  };
+
+  useEffect(() => {
+    if (!hasBackend()) return;
+    favoritesList()
+      .then(setFavorites)
+      .catch(() => {
+        /* no-op: the shortlist is optional */
+      });
   }, []);
 
+  const onToggleShortlist = async (domain: string) => {
+    const starred = favorites.some((f) => f.domain === domain);
+    const raw = rawRef.current.get(domain);
+    try {
+      if (!starred && !raw) return;
+      const items = starred ? await favoritesRemove(domain) : await favoritesAdd(raw!);
+      setFavorites(items.map((f) => ({ ...f, current: null, changes: [] })));
+      window.dispatchEvent(
+        new CustomEvent('app:flash', {
+          detail: t(starred ? 'shortlist.removed' : 'shortlist.added', { domain }),
+        })
+      );
+    } catch (_) {
+      /* no-op: keep the previous list */
+    }
+  };
+
   // App-wide flash message for status bar
   useEffect(() => {
     const handler = (e: Event) => {
@@ -229,6 +447,21 @@ const App: React.FC = () => {
             {t('app.title')}
           </h1>
           {!tokenReady ? <TokenSetup onReady={() => setTokenReady(true)} /> : null}
+          {tokenReady && tokenManage ? (
+            <TokenSetup
+              onReady={() => {
+                setTokenManage(false);
+                setRefreshTick((n) => n + 1);
+              }}
+              manage={{
+                onCancel: () => setTokenManage(false),
+                onForget: () => {
+                  setTokenManage(false);
+                  setTokenReady(false);
+                },
+              }}
+            />
+          ) : null}
           <Wizard
             prefs={prefs}
             onApply={onApply}
@@ -241,21 +474,51 @@ const App: React.FC = () => {
             <div className="sr-only" role="alert" aria-live="assertive" aria-atomic="true">
               {errorLive}
             </div>
-            <Results ref={resultsListRef} items={results} />
+            <Results
+              ref={resultsListRef}
+              items={results}
+              onPreview={setPreviewDomain}
+              watched={watched}
+              onToggleWatch={hasBackend() ? onToggleWatch : undefined}
+              shortlisted={favorites.map((f) => f.domain)}
+              onToggleShortlist={hasBackend() ? onToggleShortlist : undefined}
+            />
+            {hasBackend() ? (
+              <ExportControls
+                id="export-results"
+                label={t('export.results')}
+                items={results
+                  .map((r) => rawRef.current.get(r.domain))
+                  .filter((r): r is JsInstance => r !== undefined)}
+                disabled={results.length === 0}
+              />
+            ) : null}
           </section>
+          {hasBackend() ? <Shortlist favorites={favorites} onChange={setFavorites} /> : null}
+          {previewDomain ? (
+            <InstancePreview domain={previewDomain} onClose={() => setPreviewDomain(null)} />
+          ) : null}
+          <EvaluateDomain prefs={prefs} canPickFile={isTauri()} onEvaluated={onEvaluated} />
         </main>
 
         <LiveRegion ref={liveRef} />
       </div>
       <PreferencesModal
         open={prefsOpen}
-        onClose={() => setPrefsOpen(false)}
+        onClose={() => {
+          setPrefsOpen(false);
+          setShortcutsOpen(false);
+        }}
         lang={lang}
         onChangeLang={setLang}
         expert={expert}
         onToggleExpert={setExpert}
         brailleRefresh={brailleRefresh}
         onToggleBrailleRefresh={setBrailleRefresh}
+        checkNotices={checkAllNotices}
+        onToggleCheckNotices={setCheckAllNotices}
+        showShortcuts={shortcutsOpen}
+        canImportAdvisories={isTauri()}
       />
     </AppShell>
   );
@@ -0,0 +1,34 @@
+import { describe, it, expect } from 'vitest';
+import en from '../locales/en.json';
+import fr from '../locales/fr.json';
+
+type Catalog = { [key: string]: string | Catalog };
+
+// Dotted key -> sorted {placeholders} of its text
+function entries(catalog: Catalog, prefix = ''): Map<string, string[]> {
+  const out = new Map<string, string[]>();
+  for (const [key, value] of Object.entries(catalog)) {
+    const path = prefix ? `${prefix}.${key}` : key;
+    if (typeof value === 'string') {
+      out.set(path, [...value.matchAll(/\{(\w+)\}/g)].map((m) => m[1]).sort());
+    } else {
+      entries(value, path).forEach((names, k) => out.set(k, names));
+    }
+  }
+  return out;
+}
+
+describe('locale catalogs', () => {
+  const enEntries = entries(en as Catalog);
+  const frEntries = entries(fr as Catalog);
+
+  it('have the same keys in English and French', () => {
+    expect([...frEntries.keys()].sort()).toEqual([...enEntries.keys()].sort());
+  });
+
+  it('use the same placeholders for each key', () => {
+    for (const [key, names] of enEntries) {
+      expect(frEntries.get(key), key).toEqual(names);
+    }
+  });
+});
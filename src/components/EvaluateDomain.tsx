@@ -0,0 +1,151 @@
+import React, { useState } from 'react';
+import { useI18n } from '../i18n';
+import { evaluateDomain, evaluateDomainList, normalizeDomain, onBatchProgress } from '../lib/api';
+import type { BatchResult, DomainListSource, JsInstance } from '../lib/api';
+import type { Preferences } from '../types';
+
+type Props = {
+  prefs: Preferences;
+  // The native file dialog only exists in the desktop app
+  canPickFile: boolean;
+  onEvaluated: (items: JsInstance[]) => void;
+};
+
+export const EvaluateDomain: React.FC<Props> = ({ prefs, canPickFile, onEvaluated }) => {
+  const { t } = useI18n();
+  const [domain, setDomain] = useState('');
+  const [status, setStatus] = useState<string>('');
+  const [busy, setBusy] = useState<boolean>(false);
+  const [list, setList] = useState('');
+  const [failures, setFailures] = useState<BatchResult['errors']>([]);
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    let normalized: string;
+    try {
+      // Validation lives in the backend (same rules as directory results)
+      normalized = await normalizeDomain(domain);
+    } catch (_e) {
+      setStatus(t('evaluate.invalid'));
+      return;
+    }
+    try {
+      setBusy(true);
+      setStatus(t('evaluate.running', { domain: normalized }));
+      onEvaluated([await evaluateDomain(normalized)]);
+      setStatus(t('evaluate.done', { domain: normalized }));
+    } catch (_e) {
+      setStatus(t('evaluate.error', { domain: normalized }));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const evaluateList = async (source: DomainListSource) => {
+    setBusy(true);
+    setFailures([]);
+    setStatus(t('evaluate.list_running'));
+    const unlisten = await onBatchProgress((p) =>
+      setStatus(t('evaluate.list_progress', { done: p.done, total: p.total, domain: p.domain }))
+    );
+    try {
+      const result = await evaluateDomainList(source, prefs);
+      const total = result.items.length + result.errors.length;
+      if (total === 0) {
+        // File dialog cancelled
+        setStatus('');
+        return;
+      }
+      onEvaluated(result.items);
+      setFailures(result.errors);
+      setStatus(
+        t('evaluate.list_done', { count: result.items.length, failed: result.errors.length })
+      );
+    } catch (e) {
+      setStatus(t('evaluate.list_error', { error: String(e) }));
+    } finally {
+      unlisten();
+      setBusy(false);
+    }
+  };
+
+  const handleListSubmit = (e: React.FormEvent) => {
+    e.preventDefault();
+    void evaluateList({ kind: 'text', text: list });
+  };
+
+  const statusId = 'evaluate-status';
+  return (
+    <section className="wizard" aria-labelledby="evaluate-title" aria-busy={busy}>
+      <h2 id="evaluate-title">{t('evaluate.title')}</h2>
+      <form onSubmit={handleSubmit}>
+        <div className="row">
+          <label className="label" htmlFor="evaluate-input">
+            {t('evaluate.input')}
+          </label>
+          <input
+            id="evaluate-input"
+            type="text"
+            inputMode="url"
+            autoComplete="off"
+            spellCheck={false}
+            value={domain}
+            onChange={(e) => setDomain(e.target.value)}
+            aria-describedby="evaluate-help"
+          />
+        </div>
+        <p id="evaluate-help">{t('evaluate.help')}</p>
+        <div className="actions">
+          <button type="submit" disabled={busy || !domain.trim()} aria-controls={statusId}>
+            {t('evaluate.submit')}
+          </button>
+        </div>
+      </form>
+      <form onSubmit={handleListSubmit}>
+        <div className="row">
+          <label className="label" htmlFor="evaluate-list">
+            {t('evaluate.list')}
+          </label>
+          <textarea
+            id="evaluate-list"
+            rows={4}
+            autoComplete="off"
+            spellCheck={false}
+            value={list}
+            onChange={(e) => setList(e.target.value)}
+            aria-describedby="evaluate-list-help"
+          />
+        </div>
+        <p id="evaluate-list-help">{t('evaluate.list_help')}</p>
+        <div className="actions">
+          <button type="submit" disabled={busy || !list.trim()} aria-controls={statusId}>
+            {t('evaluate.list_submit')}
+          </button>
+          {canPickFile && (
+            <button
+              type="button"
+              disabled={busy}
+              aria-controls={statusId}
+              onClick={() => void evaluateList({ kind: 'file' })}
+            >
+              {t('evaluate.list_file')}
+            </button>
+          )}
+        </div>
+      </form>
+      <p id={statusId} role="status" aria-live="polite" aria-atomic="true">
+        {status}
+      </p>
+      {failures.length > 0 && (
+        <>
+          <h3 id="evaluate-failures-title">{t('evaluate.list_failures')}</h3>
+          <ul aria-labelledby="evaluate-failures-title">
+            {failures.map((f) => (
+              <li key={f.domain}>{t('evaluate.list_failure', f)}</li>
+            ))}
+          </ul>
+        </>
+      )}
+    </section>
+  );
+};
@@ -0,0 +1,50 @@
+import React, { useState } from 'react';
+import { useI18n } from '../i18n';
+import { exportResults } from '../lib/api';
+import type { ExportFormat, JsInstance } from '../lib/api';
+
+type Props = {
+  id: string;
+  label: string;
+  // Ranked results in display order; omitted to export the shortlist
+  items?: JsInstance[];
+  disabled?: boolean;
+};
+
+export const ExportControls: React.FC<Props> = ({ id, label, items, disabled }) => {
+  const { t } = useI18n();
+  const [format, setFormat] = useState<ExportFormat>('html');
+
+  const run = async () => {
+    try {
+      const path = await exportResults(items ?? null, format);
+      if (!path) return;
+      window.dispatchEvent(new CustomEvent('app:flash', { detail: t('export.done', { path }) }));
+    } catch (e) {
+      window.dispatchEvent(
+        new CustomEvent('app:flash', { detail: t('export.error', { error: String(e) }) })
+      );
+    }
+  };
+
+  return (
+    <div className="actions">
+      <label className="label" htmlFor={`${id}-format`}>
+        {t('export.format')}
+      </label>
+      <select
+        id={`${id}-format`}
+        value={format}
+        onChange={(e) => setFormat(e.target.value as ExportFormat)}
+      >
+        <option value="html">{t('export.format_html')}</option>
+        <option value="csv">{t('export.format_csv')}</option>
+        <option value="markdown">{t('export.format_markdown')}</option>
+        <option value="json">{t('export.format_json')}</option>
+      </select>
+      <button type="button" disabled={disabled} onClick={() => void run()}>
+        {label}
+      </button>
+    </div>
+  );
+};
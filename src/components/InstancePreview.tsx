@@ -0,0 +1,105 @@
+import React, { useEffect, useRef, useState } from 'react';
+import { useI18n } from '../i18n';
+import { checkUptime, previewInstance } from '../lib/api';
+import type { InstancePreview as Preview, UptimeStats } from '../lib/api';
+
+type Props = { domain: string; onClose: () => void };
+
+export const InstancePreview: React.FC<Props> = ({ domain, onClose }) => {
+  const { t } = useI18n();
+  const [preview, setPreview] = useState<Preview | null>(null);
+  const [status, setStatus] = useState<string>('');
+  const [uptime, setUptime] = useState<UptimeStats | null>(null);
+  const headingRef = useRef<HTMLHeadingElement | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    setPreview(null);
+    setUptime(null);
+    setStatus(t('preview.loading', { domain }));
+    headingRef.current?.focus();
+    previewInstance(domain)
+      .then((p) => {
+        if (cancelled) return;
+        setPreview(p);
+        setStatus(t('preview.done', { count: p.posts.length }));
+      })
+      .catch(() => {
+        if (!cancelled) setStatus(t('preview.error', { domain }));
+      });
+    // Looking at an instance adds a sample to its local uptime history
+    checkUptime([domain])
+      .then(([stats]) => {
+        if (!cancelled && stats) setUptime(stats);
+      })
+      .catch(() => {
+        /* no-op: uptime figures are optional */
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [domain, t]);
+
+  return (
+    <section className="wizard" aria-labelledby="preview-title" aria-busy={!preview}>
+      <h2 id="preview-title" ref={headingRef} tabIndex={-1}>
+        {t('preview.title', { domain })}
+      </h2>
+      <p role="status" aria-live="polite" aria-atomic="true">
+        {status}
+      </p>
+      {uptime?.uptime7d != null ? (
+        <p>
+          {t('preview.uptime', {
+            week: Math.round(uptime.uptime7d * 100),
+            month: Math.round((uptime.uptime30d ?? uptime.uptime7d) * 100),
+            samples: uptime.samples,
+          })}
+          {uptime.p50Ms != null
+            ? ` ${t('preview.latency', { p50: uptime.p50Ms, p95: uptime.p95Ms ?? uptime.p50Ms })}`
+            : null}
+        </p>
+      ) : null}
+      {preview && !preview.timelinePublic ? <p>{t('preview.private')}</p> : null}
+      {preview && preview.trendingTags.length > 0 ? (
+        <>
+          <h3>{t('preview.trends')}</h3>
+          <ul>
+            {preview.trendingTags.map((tag) => (
+              <li key={tag.name}>
+                #{tag.name} ({t('preview.tag_uses', { uses: tag.uses, accounts: tag.accounts })})
+              </li>
+            ))}
+          </ul>
+        </>
+      ) : null}
+      {preview && preview.posts.length > 0 ? (
+        <>
+          <h3>{t('preview.posts')}</h3>
+          <ul>
+            {preview.posts.map((post, idx) => (
+              <li key={post.url ?? idx} lang={post.language ?? undefined}>
+                <p>
+                  <strong>{post.author}</strong>
+                </p>
+                {post.contentWarning ? (
+                  <details>
+                    <summary>{t('preview.cw', { text: post.contentWarning })}</summary>
+                    <p className="preview-text">{post.text}</p>
+                  </details>
+                ) : (
+                  <p className="preview-text">{post.text}</p>
+                )}
+              </li>
+            ))}
+          </ul>
+        </>
+      ) : null}
+      <div className="actions">
+        <button type="button" onClick={onClose}>
+          {t('preview.close')}
+        </button>
+      </div>
+    </section>
+  );
+};
@@ -1,6 +1,8 @@
-import React, { useEffect, useRef } from 'react';
+import React, { useEffect, useRef, useState } from 'react';
 import { useI18n } from '../i18n';
 import type { Lang } from '../i18n';
+import { advisoriesInfo, importAdvisories, purgeUptimeHistory } from '../lib/api';
+import type { AdvisoriesInfo } from '../lib/api';
 
 type Props = {
   open: boolean;
@@ -11,6 +13,12 @@ type Props = {
   onToggleExpert: (v: boolean) => void;
   brailleRefresh: boolean;
   onToggleBrailleRefresh: (v: boolean) => void;
+  checkNotices: boolean;
+  onToggleCheckNotices: (v: boolean) => void;
+  // Opened from Help > Keyboard shortcuts: start on that section
+  showShortcuts?: boolean;
+  // Importing needs the native file dialog
+  canImportAdvisories?: boolean;
 };
 
 export const PreferencesModal: React.FC<Props> = ({
@@ -22,11 +30,25 @@ export const PreferencesModal: React.FC<Props> = ({
   onToggleExpert,
   brailleRefresh,
   onToggleBrailleRefresh,
+  checkNotices,
+  onToggleCheckNotices,
+  showShortcuts,
+  canImportAdvisories,
 }) => {
   const { t } = useI18n();
   const backdropRef = useRef<HTMLDivElement | null>(null);
   const dialogRef = useRef<HTMLDivElement | null>(null);
   const lastFocusRef = useRef<HTMLElement | null>(null);
+  const [dataStatus, setDataStatus] = useState<string>('');
+  const [advisories, setAdvisories] = useState<AdvisoriesInfo | null>(null);
+
+  // Date of the advisory list, so an outdated one is noticed
+  useEffect(() => {
+    if (!open) return;
+    advisoriesInfo()
+      .then(setAdvisories)
+      .catch(() => setAdvisories(null));
+  }, [open]);
 
   useEffect(() => {
     if (!open) return;
@@ -51,7 +73,13 @@ export const PreferencesModal: React.FC<Props> = ({
       if (focusables.length > 0) focusables[0].focus();
       else dialog?.focus();
     };
-    focusFirst();
+    const shortcuts = dialog?.querySelector<HTMLElement>('#prefs-shortcuts-title');
+    if (showShortcuts && shortcuts) {
+      shortcuts.focus();
+      shortcuts.scrollIntoView({ block: 'start' });
+    } else {
+      focusFirst();
+    }
     const onWinKey = (e: KeyboardEvent) => {
       if (e.key === 'Escape' || ((e.ctrlKey || e.metaKey) && (e.key === 'w' || e.key === 'W'))) {
         e.preventDefault();
@@ -92,7 +120,7 @@ export const PreferencesModal: React.FC<Props> = ({
       window.removeEventListener('focusin', onFocusIn, true);
       lastFocusRef.current?.focus();
     };
-  }, [open, onClose]);
+  }, [open, onClose, showShortcuts]);
 
   if (!open) return null;
   return (
@@ -156,6 +184,18 @@ export const PreferencesModal: React.FC<Props> = ({
                 {t('prefs.expert_mode')}
               </label>
             </div>
+            <div className="row" style={{ marginTop: '.5rem' }}>
+              <label>
+                <input
+                  type="checkbox"
+                  checked={checkNotices}
+                  onChange={(e) => onToggleCheckNotices(e.target.checked)}
+                  aria-describedby="prefs-notices-help"
+                />{' '}
+                {t('prefs.check_notices')}
+              </label>
+            </div>
+            <p id="prefs-notices-help">{t('prefs.check_notices_help')}</p>
           </section>
           <section aria-labelledby="prefs-a11y-title" style={{ marginBottom: '1rem' }}>
             <h3 id="prefs-a11y-title">{t('prefs.accessibility')}</h3>
@@ -170,8 +210,59 @@ export const PreferencesModal: React.FC<Props> = ({
               </label>
             </div>
           </section>
+          <section aria-labelledby="prefs-data-title" style={{ marginBottom: '1rem' }}>
+            <h3 id="prefs-data-title">{t('prefs.data')}</h3>
+            <p>{t('prefs.uptime_help')}</p>
+            <div className="row">
+              <button
+                type="button"
+                onClick={async () => {
+                  try {
+                    await purgeUptimeHistory();
+                    setDataStatus(t('prefs.uptime_purged'));
+                  } catch (_e) {
+                    setDataStatus(t('prefs.uptime_purge_error'));
+                  }
+                }}
+              >
+                {t('prefs.uptime_purge')}
+              </button>
+            </div>
+            {advisories && (
+              <p>
+                {t(advisories.imported ? 'prefs.advisories_imported' : 'prefs.advisories_bundled', {
+                  date: advisories.updated,
+                  count: advisories.count,
+                })}
+              </p>
+            )}
+            {advisories && canImportAdvisories && (
+              <div className="row">
+                <button
+                  type="button"
+                  onClick={async () => {
+                    try {
+                      const info = await importAdvisories();
+                      if (!info) return;
+                      setAdvisories(info);
+                      setDataStatus(t('prefs.advisories_import_done', { date: info.updated }));
+                    } catch (e) {
+                      setDataStatus(t('prefs.advisories_import_error', { error: String(e) }));
+                    }
+                  }}
+                >
+                  {t('prefs.advisories_import')}
+                </button>
+              </div>
+            )}
+            <p role="status" aria-live="polite" aria-atomic="true">
+              {dataStatus}
+            </p>
+          </section>
           <section aria-labelledby="prefs-shortcuts-title">
-            <h3 id="prefs-shortcuts-title">{t('prefs.shortcuts')}</h3>
+            <h3 id="prefs-shortcuts-title" tabIndex={-1}>
+              {t('prefs.shortcuts')}
+            </h3>
             <ul>
               <li>{t('prefs.shortcut_enter_open')}</li>
               <li>{t('prefs.shortcut_tab_copy')}</li>
@@ -179,6 +270,11 @@ export const PreferencesModal: React.FC<Props> = ({
               <li>{t('prefs.shortcut_refresh')}</li>
               <li>{t('prefs.shortcut_prefs')}</li>
               <li>{t('prefs.shortcut_close_prefs')}</li>
+              <li>{t('prefs.shortcut_export')}</li>
+              <li>{t('prefs.shortcut_token')}</li>
+              <li>{t('prefs.shortcut_clear_cache')}</li>
+              <li>{t('prefs.shortcut_help')}</li>
+              <li>{t('prefs.shortcut_quit')}</li>
             </ul>
           </section>
         </div>
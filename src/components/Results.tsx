@@ -3,16 +3,9 @@ import type { Instance } from '../types';
 import { useI18n } from '../i18n';
 import { openExternal } from '../lib/open-external';
 
-function isValidDomain(domain: string): boolean {
-  const d = domain.trim().toLowerCase();
-  // Basic hostname validation (RFC-1123 style, no trailing dot)
-  const re =
-    /^(?:[a-z0-9](?:[a-z0-9-]{0,61}[a-z0-9])?)(?:\.[a-z0-9](?:[a-z0-9-]{0,61}[a-z0-9])?)+$/i;
-  return re.test(d);
-}
-
-function buildInstanceUrl(domain: string): string | null {
-  if (!isValidDomain(domain)) return null;
+// Domains are validated and normalized by the backend (normalize_domain)
+function buildInstanceUrl(domain: string | undefined): string | null {
+  if (!domain) return null;
   return `https://${domain}`;
 }
 
@@ -46,10 +39,17 @@ async function copyText(text: string): Promise<boolean> {
   return ok;
 }
 
-type Props = { items: Instance[] };
+type Props = {
+  items: Instance[];
+  onPreview?: (domain: string) => void;
+  watched?: string[];
+  onToggleWatch?: (domain: string) => void;
+  shortlisted?: string[];
+  onToggleShortlist?: (domain: string) => void;
+};
 
 export const Results = React.forwardRef<HTMLUListElement, Props>(function Results(
-  { items },
+  { items, onPreview, watched, onToggleWatch, shortlisted, onToggleShortlist },
   listRef
 ) {
   const { t } = useI18n();
@@ -192,6 +192,8 @@ export const Results = React.forwardRef<HTMLUListElement, Props>(function Result
           const titleId = `title-${idSafe}`;
           const descId = `desc-${idSafe}`;
           const factsId = `facts-${idSafe}`;
+          const warnId = `warn-${idSafe}`;
+          const noticeId = `notice-${idSafe}`;
           return (
             <li
               key={it.domain}
@@ -200,7 +202,13 @@ export const Results = React.forwardRef<HTMLUListElement, Props>(function Result
               role="option"
               aria-selected={active === idx}
               aria-labelledby={`${titleId} ${descId}`}
-              aria-describedby={factsId}
+              aria-describedby={[
+                it.notice ? noticeId : null,
+                it.vulnerability ? warnId : null,
+                factsId,
+              ]
+                .filter(Boolean)
+                .join(' ')}
               aria-keyshortcuts="Enter, Control+O, Meta+O, Control+C, Meta+C, ArrowUp, ArrowDown, Home, End"
               onMouseEnter={() => setActive(idx)}
             >
@@ -220,12 +228,92 @@ export const Results = React.forwardRef<HTMLUListElement, Props>(function Result
                   </a>
                 </h3>
                 <p id={descId}>{it.description}</p>
+                {it.notice ? (
+                  <p id={noticeId} className="instance-warning">
+                    <strong>{t(`results.notice_${it.notice.kind}`)}</strong> {it.notice.excerpt}
+                  </p>
+                ) : null}
+                {it.vulnerability ? (
+                  <p id={warnId} className="instance-warning">
+                    <strong>
+                      {t(
+                        it.vulnerability.severity === 'critical'
+                          ? 'results.vulnerable_critical'
+                          : 'results.vulnerable',
+                        {
+                          version: it.vulnerability.version,
+                          ids: it.vulnerability.advisories.map((a) => a.id).join(', '),
+                        }
+                      )}
+                    </strong>
+                  </p>
+                ) : null}
                 <p id={factsId}>
                   <span>{it.languages.join(', ').toUpperCase()}</span>
                   {' · '}
-                  <span>{it.signups === 'open' ? t('results.open') : t('results.approval')}</span>
+                  <span>
+                    {it.signups === 'open'
+                      ? t('results.open')
+                      : it.signups === 'closed'
+                        ? t('results.closed')
+                        : t('results.approval')}
+                  </span>
                   {' · '}
                   <span>{it.sizeLabel}</span>
+                  {it.software ? (
+                    <>
+                      {' · '}
+                      <span>
+                        {it.software.name} {it.software.version}
+                      </span>
+                    </>
+                  ) : null}
+                  {it.accessibility?.score != null ? (
+                    <>
+                      {' · '}
+                      <span>
+                        {t('results.accessibility', {
+                          score: Math.round(it.accessibility.score * 100),
+                        })}
+                      </span>
+                    </>
+                  ) : null}
+                  {it.security ? (
+                    <>
+                      {' · '}
+                      <span>{t('results.security', { grade: it.security.grade })}</span>
+                    </>
+                  ) : null}
+                  {it.activity && it.activity.trendLabel !== 'unknown' ? (
+                    <>
+                      {' · '}
+                      <span>
+                        {t(`results.trend_${it.activity.trendLabel}`, {
+                          logins: Math.round(it.activity.weeklyLogins ?? 0),
+                        })}
+                      </span>
+                    </>
+                  ) : null}
+                  {it.activity?.peers != null ? (
+                    <>
+                      {' · '}
+                      <span>{t('results.peers', { count: it.activity.peers })}</span>
+                    </>
+                  ) : null}
+                  {it.privacy ? (
+                    <>
+                      {' · '}
+                      <span>
+                        {it.privacy.cdn
+                          ? t('results.privacy_cdn', { cdn: it.privacy.cdn })
+                          : it.privacy.thirdPartyOrigins.length
+                            ? t('results.privacy_third_party', {
+                                count: it.privacy.thirdPartyOrigins.length,
+                              })
+                            : t('results.privacy_ok')}
+                      </span>
+                    </>
+                  ) : null}
                 </p>
               </div>
               {active === idx && (
@@ -262,6 +350,32 @@ export const Results = React.forwardRef<HTMLUListElement, Props>(function Result
                 >
                   {t('results.openBrowser')}
                 </button>
+                {onPreview ? (
+                  <button
+                    tabIndex={controlsIdx === idx ? 0 : -1}
+                    onClick={() => onPreview(it.domain)}
+                  >
+                    {t('results.preview')}
+                  </button>
+                ) : null}
+                {onToggleWatch ? (
+                  <button
+                    tabIndex={controlsIdx === idx ? 0 : -1}
+                    aria-pressed={watched?.includes(it.domain) ?? false}
+                    onClick={() => onToggleWatch(it.domain)}
+                  >
+                    {t('results.watch')}
+                  </button>
+                ) : null}
+                {onToggleShortlist ? (
+                  <button
+                    tabIndex={controlsIdx === idx ? 0 : -1}
+                    aria-pressed={shortlisted?.includes(it.domain) ?? false}
+                    onClick={() => onToggleShortlist(it.domain)}
+                  >
+                    {t('results.shortlist')}
+                  </button>
+                ) : null}
               </div>
             </li>
           );
@@ -0,0 +1,136 @@
+import React, { useState } from 'react';
+import { useI18n } from '../i18n';
+import {
+  favoritesList,
+  favoritesRemove,
+  favoritesReorder,
+  favoritesSetNote,
+} from '../lib/api';
+import type { Favorite, FavoriteView } from '../lib/api';
+import { ExportControls } from './ExportControls';
+
+type Props = {
+  favorites: FavoriteView[];
+  onChange: (favorites: FavoriteView[]) => void;
+};
+
+// Keep diffs from the last check when the list itself changes
+function merge(items: Favorite[], previous: FavoriteView[]): FavoriteView[] {
+  return items.map((f) => {
+    const old = previous.find((p) => p.domain === f.domain);
+    return { ...f, current: old?.current ?? null, changes: old?.changes ?? [] };
+  });
+}
+
+export const Shortlist: React.FC<Props> = ({ favorites, onChange }) => {
+  const { t } = useI18n();
+  const [status, setStatus] = useState<string>('');
+  const [busy, setBusy] = useState<boolean>(false);
+
+  const move = async (idx: number, delta: number) => {
+    const order = favorites.map((f) => f.domain);
+    const [domain] = order.splice(idx, 1);
+    order.splice(idx + delta, 0, domain);
+    onChange(merge(await favoritesReorder(order), favorites));
+    setStatus(t('shortlist.moved', { domain, position: idx + delta + 1 }));
+  };
+
+  const checkChanges = async () => {
+    setBusy(true);
+    setStatus(t('shortlist.checking'));
+    try {
+      const views = await favoritesList(true);
+      onChange(views);
+      const changed = views.filter((v) => v.changes.length > 0).length;
+      setStatus(t('shortlist.checked', { count: changed }));
+    } catch (_e) {
+      setStatus(t('shortlist.check_error'));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <section className="wizard" aria-labelledby="shortlist-title" aria-busy={busy}>
+      <h2 id="shortlist-title">{t('shortlist.title')}</h2>
+      {favorites.length === 0 ? <p>{t('shortlist.empty')}</p> : null}
+      <ol>
+        {favorites.map((f, idx) => {
+          const noteId = `note-${f.domain.replace(/[^a-z0-9-]/gi, '-')}`;
+          return (
+            <li key={f.domain}>
+              <h3>{f.domain}</h3>
+              <p>{f.snapshot.description}</p>
+              {f.changes.length > 0 ? (
+                <>
+                  <p>
+                    <strong>{t('shortlist.changed')}</strong>
+                  </p>
+                  <ul>
+                    {f.changes.map((c) => (
+                      <li key={c.field}>
+                        {t('shortlist.change', {
+                          field: t(`shortlist.field_${c.field}`),
+                          before: c.before || t('shortlist.none'),
+                          after: c.after || t('shortlist.none'),
+                        })}
+                      </li>
+                    ))}
+                  </ul>
+                </>
+              ) : null}
+              <div className="row">
+                <label className="label" htmlFor={noteId}>
+                  {t('shortlist.note')}
+                </label>
+                <textarea
+                  id={noteId}
+                  defaultValue={f.note}
+                  onBlur={async (e) => {
+                    if (e.target.value === f.note) return;
+                    onChange(merge(await favoritesSetNote(f.domain, e.target.value), favorites));
+                    setStatus(t('shortlist.note_saved', { domain: f.domain }));
+                  }}
+                />
+              </div>
+              <div className="actions">
+                <button type="button" disabled={idx === 0} onClick={() => void move(idx, -1)}>
+                  {t('shortlist.move_up')}
+                </button>
+                <button
+                  type="button"
+                  disabled={idx === favorites.length - 1}
+                  onClick={() => void move(idx, 1)}
+                >
+                  {t('shortlist.move_down')}
+                </button>
+                <button
+                  type="button"
+                  onClick={async () => {
+                    onChange(merge(await favoritesRemove(f.domain), favorites));
+                    setStatus(t('shortlist.removed', { domain: f.domain }));
+                  }}
+                >
+                  {t('shortlist.remove')}
+                </button>
+              </div>
+            </li>
+          );
+        })}
+      </ol>
+      {favorites.length > 0 ? (
+        <div className="actions">
+          <button type="button" disabled={busy} onClick={() => void checkChanges()}>
+            {t('shortlist.check')}
+          </button>
+        </div>
+      ) : null}
+      {favorites.length > 0 ? (
+        <ExportControls id="export-shortlist" label={t('export.shortlist')} />
+      ) : null}
+      <p role="status" aria-live="polite" aria-atomic="true">
+        {status}
+      </p>
+    </section>
+  );
+};
@@ -1,22 +1,42 @@
 import React, { useState } from 'react';
 import { useI18n } from '../i18n';
-import { tokenStatus, testToken, saveToken } from '../lib/api';
+import { tokenStatus, testToken, saveToken, clearToken } from '../lib/api';
 import { openExternal } from '../lib/open-external';
 
-type Props = { onReady: () => void };
+type Props = {
+  onReady: () => void;
+  // Opened from the menu with a token already set: replace or forget it
+  manage?: { onCancel: () => void; onForget: () => void };
+};
 
-export const TokenSetup: React.FC<Props> = ({ onReady }) => {
+export const TokenSetup: React.FC<Props> = ({ onReady, manage }) => {
   const { t } = useI18n();
   const [token, setToken] = useState('');
   const [persist, setPersist] = useState(true);
   const [status, setStatus] = useState<string>('');
   const [busy, setBusy] = useState<boolean>(false);
+  const inputRef = React.useRef<HTMLInputElement | null>(null);
+  const managing = manage !== undefined;
 
   React.useEffect(() => {
+    if (managing) return;
     tokenStatus().then((ok) => {
       if (ok) onReady();
     });
-  }, [onReady]);
+  }, [onReady, managing]);
+
+  React.useEffect(() => {
+    if (managing) inputRef.current?.focus();
+  }, [managing]);
+
+  const handleForget = async () => {
+    try {
+      await clearToken();
+      manage?.onForget();
+    } catch (_e) {
+      setStatus(t('token.forget_error'));
+    }
+  };
 
   const handleTest = async () => {
     try {
@@ -58,6 +78,7 @@ export const TokenSetup: React.FC<Props> = ({ onReady }) => {
           {t('token.input')}
         </label>
         <input
+          ref={inputRef}
           id="token-input"
           type="password"
           value={token}
@@ -85,6 +106,16 @@ export const TokenSetup: React.FC<Props> = ({ onReady }) => {
         >
           {t('token.save')}
         </button>
+        {manage ? (
+          <>
+            <button type="button" onClick={() => void handleForget()} disabled={busy}>
+              {t('token.forget')}
+            </button>
+            <button type="button" onClick={manage.onCancel}>
+              {t('token.cancel')}
+            </button>
+          </>
+        ) : null}
       </div>
       <p id={statusId} role="status" aria-live="assertive" aria-atomic="true">
         {status}
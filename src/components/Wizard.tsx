@@ -1,9 +1,19 @@
 import React, { useId, useState } from 'react';
 import { useI18n } from '../i18n';
-import type { Preferences } from '../types';
+import type { Preferences, Software } from '../types';
 import { CheckboxList } from './CheckboxList';
 import { languageDisplayName } from '../lib/languages';
 
+const SOFTWARE: { id: Software; label: string }[] = [
+  { id: 'mastodon', label: 'Mastodon' },
+  { id: 'glitch-soc', label: 'glitch-soc' },
+  { id: 'hometown', label: 'Hometown' },
+  { id: 'pleroma', label: 'Pleroma' },
+  { id: 'akkoma', label: 'Akkoma' },
+  { id: 'gotosocial', label: 'GoToSocial' },
+  { id: 'misskey', label: 'Misskey' },
+];
+
 type Props = {
   prefs: Preferences;
   onApply: (p: Preferences) => void;
@@ -113,12 +123,14 @@ export const Wizard: React.FC<Props> = ({
             id="moderation-select"
             value={local.moderation}
             onChange={(e) => update('moderation', e.target.value as Preferences['moderation'])}
+            aria-describedby="moderation-help"
           >
             <option value="any">{t('wizard.indifferent')}</option>
             <option value="open">{t('wizard.moderation_open')}</option>
             <option value="balanced">{t('wizard.moderation_balanced')}</option>
             <option value="strict">{t('wizard.moderation_strict')}</option>
           </select>
+          <p id="moderation-help">{t('wizard.moderation_help')}</p>
         </div>
 
         <div className="row">
@@ -160,6 +172,105 @@ export const Wizard: React.FC<Props> = ({
           </div>
         )}
 
+        {expert && (
+          <div className="row">
+            <label className="label" htmlFor="software-select">
+              {t('wizard.software')}
+              {local.software === 'any' && (
+                <span className="badge-muted">{t('wizard.indifferent')}</span>
+              )}
+            </label>
+            <select
+              id="software-select"
+              value={local.software}
+              onChange={(e) => update('software', e.target.value as Preferences['software'])}
+            >
+              <option value="any">{t('wizard.indifferent')}</option>
+              {SOFTWARE.map((sw) => (
+                <option key={sw.id} value={sw.id}>
+                  {sw.label}
+                </option>
+              ))}
+            </select>
+          </div>
+        )}
+
+        {expert && (
+          <div className="row">
+            <label className="label" htmlFor="security-select">
+              {t('wizard.security')}
+              {local.security === 'any' && (
+                <span className="badge-muted">{t('wizard.indifferent')}</span>
+              )}
+            </label>
+            <select
+              id="security-select"
+              value={local.security}
+              onChange={(e) => update('security', e.target.value as Preferences['security'])}
+            >
+              <option value="any">{t('wizard.indifferent')}</option>
+              <option value="A">{t('wizard.security_min', { grade: 'A' })}</option>
+              <option value="B">{t('wizard.security_min', { grade: 'B' })}</option>
+              <option value="C">{t('wizard.security_min', { grade: 'C' })}</option>
+            </select>
+          </div>
+        )}
+
+        {expert && (
+          <div className="row">
+            <label className="label" htmlFor="privacy-select">
+              {t('wizard.privacy')}
+              {local.privacy === 'any' && (
+                <span className="badge-muted">{t('wizard.indifferent')}</span>
+              )}
+            </label>
+            <select
+              id="privacy-select"
+              value={local.privacy}
+              onChange={(e) => update('privacy', e.target.value as Preferences['privacy'])}
+            >
+              <option value="any">{t('wizard.indifferent')}</option>
+              <option value="no_cdn">{t('wizard.privacy_no_cdn')}</option>
+              <option value="no_third_party">{t('wizard.privacy_no_third_party')}</option>
+              <option value="strict">{t('wizard.privacy_strict')}</option>
+            </select>
+          </div>
+        )}
+
+        {expert && (
+          <div className="row">
+            <label className="label" htmlFor="vulnerable-select">
+              {t('wizard.vulnerable')}
+            </label>
+            <select
+              id="vulnerable-select"
+              value={local.vulnerable}
+              onChange={(e) => update('vulnerable', e.target.value as Preferences['vulnerable'])}
+            >
+              <option value="any">{t('wizard.vulnerable_rank')}</option>
+              <option value="hide">{t('wizard.vulnerable_hide')}</option>
+            </select>
+          </div>
+        )}
+
+        {expert && (
+          <div className="row">
+            <label className="label" htmlFor="sort-select">
+              {t('wizard.sort')}
+            </label>
+            <select
+              id="sort-select"
+              value={local.sort}
+              onChange={(e) => update('sort', e.target.value as Preferences['sort'])}
+            >
+              <option value="relevance">{t('wizard.sort_relevance')}</option>
+              <option value="activity">{t('wizard.sort_activity')}</option>
+              <option value="growth">{t('wizard.sort_growth')}</option>
+              <option value="peers">{t('wizard.sort_peers')}</option>
+            </select>
+          </div>
+        )}
+
         <div className="row">
           <label className="label" htmlFor="nsfw-select">
             {t('wizard.nsfw')}
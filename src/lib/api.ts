@@ -1,24 +1,218 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invoke as tauriInvoke } from '@tauri-apps/api/core';
+import type { Preferences } from '../types';
+
+// Set by `instances-finder --serve`, which answers commands over HTTP
+export function isBrowserServer(): boolean {
+  if (typeof document === 'undefined') return false;
+  const meta = document.querySelector('meta[name="instances-finder-backend"]');
+  return meta?.getAttribute('content') === 'http';
+}
+
+// Same contract as Tauri's invoke: resolves to the result, rejects with the error string
+async function invoke<T>(command: string, args?: Record<string, unknown>): Promise<T> {
+  if (!isBrowserServer()) return tauriInvoke<T>(command, args);
+  const res = await fetch(`/api/${command}`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    credentials: 'same-origin',
+    body: JSON.stringify(args ?? {}),
+  });
+  const body = await res.json().catch(() => null);
+  if (!res.ok) return Promise.reject(body?.error ?? res.statusText);
+  return body as T;
+}
 
 export type FetchParams = {
-  language?: string;
+  language?: string; // one code or several: "fr,en"
   include_closed?: boolean;
   include_down?: boolean;
   max?: number;
   signups?: 'open' | 'approval';
   region?: 'eu' | 'na' | 'other';
   size?: 'small' | 'medium' | 'large';
+  software?: string;
+  min_security?: SecurityGrade;
+  exclude_cdn?: boolean;
+  exclude_third_party?: boolean;
+  hide_vulnerable?: boolean;
+  check_notices?: boolean;
+  check_activity?: boolean;
+};
+
+export type ModerationReport = {
+  public: boolean;
+  rulesPublic: boolean;
+  rulesCount: number;
+  rules: string[];
+  domainBlocksPublic: boolean;
+  suspended: number;
+  silenced: number;
+  style: 'open' | 'balanced' | 'strict' | 'unknown';
+};
+
+export type AccessibilityReport = {
+  timelinePublic: boolean;
+  sampledPosts: number;
+  mediaCount: number;
+  mediaWithAlt: number;
+  altTextRatio: number | null;
+  hashtagCount: number;
+  camelCaseRatio: number | null;
+  mentionsAccessibility: boolean;
+  score: number | null;
+};
+
+export type SoftwareInfo = {
+  name: string;
+  version: string;
+  users: number | null;
+  activeMonth: number | null;
+  localPosts: number | null;
+  openRegistrations: boolean;
+};
+
+export type SecurityGrade = 'A' | 'B' | 'C' | 'D' | 'E' | 'F';
+
+export type SecuritySummary = {
+  certificateValid: boolean;
+  certificateError: string | null;
+  daysUntilExpiry: number | null;
+  tlsVersion: string | null;
+  hsts: boolean | null; // null when the home page could not be read
+  hstsMaxAge: number | null;
+  csp: boolean | null;
+  httpRedirectsToHttps: boolean | null;
+  grade: SecurityGrade;
+};
+
+export type PrivacySummary = {
+  cdn: string | null;
+  server: string | null;
+  thirdPartyOrigins: string[];
+};
+
+export type Advisory = {
+  id: string;
+  title: string;
+  severity: 'low' | 'medium' | 'high' | 'critical';
+  published: string;
+  fixed: string[];
+};
+
+export type VulnerabilityReport = {
+  version: string;
+  advisories: Advisory[];
+  severity: Advisory['severity'];
+  criticalUnpatchedDays: number | null;
+};
+
+export type AdvisoriesInfo = {
+  updated: string;
+  count: number;
+  imported: boolean;
+};
+
+export type ServiceNotice = {
+  kind: 'shutdown' | 'migration' | 'read_only';
+  source: 'announcement' | 'description';
+  excerpt: string;
+  publishedAt: string | null;
+};
+
+export type PreviewPost = {
+  author: string;
+  createdAt: string;
+  language: string | null;
+  contentWarning: string | null;
+  text: string;
+  url: string | null;
+};
+
+export type TrendingTag = {
+  name: string;
+  uses: number;
+  accounts: number;
+};
+
+export type InstancePreview = {
+  domain: string;
+  timelinePublic: boolean;
+  posts: PreviewPost[];
+  trendingTags: TrendingTag[];
+};
+
+export type ActivityReport = {
+  peers: number | null;
+  weeklyStatuses: number | null;
+  weeklyLogins: number | null;
+  weeklyRegistrations: number | null;
+  trend: number | null;
+  trendLabel: 'growing' | 'stable' | 'declining' | 'unknown';
+  loginsPerUser: number | null;
+};
+
+export type UptimeStats = {
+  domain: string;
+  samples: number;
+  lastChecked: number | null;
+  uptime7d: number | null;
+  uptime30d: number | null;
+  p50Ms: number | null;
+  p95Ms: number | null;
+};
+
+export type WatchItem = {
+  domain: string;
+  addedAt: number;
+  signups: JsInstance['signups'] | null;
+  checkedAt: number | null;
+};
+
+export type WatchChange = {
+  domain: string;
+  from: JsInstance['signups'];
+  to: JsInstance['signups'];
+};
+
+export type Favorite = {
+  domain: string;
+  note: string;
+  addedAt: number;
+  snapshot: JsInstance;
+};
+
+export type FieldChange = {
+  field: string;
+  before: string;
+  after: string;
+};
+
+export type FavoriteView = Favorite & {
+  current: JsInstance | null;
+  changes: FieldChange[];
 };
 
 export type JsInstance = {
   domain: string;
   description: string;
   languages: string[];
-  signups: 'open' | 'approval';
+  signups: 'open' | 'approval' | 'closed';
   size: number;
   sizeLabel: string;
   region: string;
   availability: number;
+  users?: number;
+  activeMonth?: number;
+  moderation?: ModerationReport;
+  accessibility?: AccessibilityReport;
+  software?: SoftwareInfo;
+  security?: SecuritySummary;
+  privacy?: PrivacySummary;
+  vulnerability?: VulnerabilityReport;
+  notice?: ServiceNotice;
+  activity?: ActivityReport;
+  // Read from the instance itself rather than the directory
+  probed?: boolean;
 };
 
 export async function tokenStatus(): Promise<boolean> {
@@ -44,6 +238,11 @@ export async function fetchInstances(
   return invoke('fetch_instances', { params, bypass_cache: bypassCache });
 }
 
+// Backend labels, errors and the native menu follow the UI language
+export async function setLocale(lang: string): Promise<void> {
+  await invoke('set_locale', { lang });
+}
+
 export async function clearInstancesCache(): Promise<void> {
   await invoke('clear_instances_cache');
 }
@@ -51,3 +250,169 @@ export async function clearInstancesCache(): Promise<void> {
 export async function fetchLanguages(): Promise<string[]> {
   return invoke('fetch_languages');
 }
+
+export async function advisoriesInfo(): Promise<AdvisoriesInfo> {
+  return invoke('advisories_info');
+}
+
+export async function importAdvisories(path?: string): Promise<AdvisoriesInfo | null> {
+  return invoke('import_advisories', { path });
+}
+
+export async function previewInstance(domain: string): Promise<InstancePreview> {
+  return invoke('preview_instance', { domain });
+}
+
+export async function normalizeDomain(domain: string): Promise<string> {
+  return invoke('normalize_domain', { domain });
+}
+
+export async function evaluateDomain(domain: string): Promise<JsInstance> {
+  return invoke('evaluate_domain', { domain });
+}
+
+export type DomainListSource = { kind: 'text'; text: string } | { kind: 'file'; path?: string };
+
+export type BatchResult = {
+  items: JsInstance[];
+  errors: { domain: string; reason: string }[];
+};
+
+export type BatchProgress = { done: number; total: number; domain: string; ok: boolean };
+
+// Without a path, the backend asks for a .txt/.csv file with a native dialog.
+export async function evaluateDomainList(
+  source: DomainListSource,
+  prefs?: Preferences
+): Promise<BatchResult> {
+  return invoke('evaluate_domain_list', { source, prefs });
+}
+
+// Progress and change events only exist in the desktop app
+export async function onBatchProgress(cb: (p: BatchProgress) => void): Promise<() => void> {
+  if (isBrowserServer()) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<BatchProgress>('batch://progress', (e) => cb(e.payload));
+}
+
+export async function checkNotices(domains: string[]): Promise<Record<string, ServiceNotice>> {
+  return invoke('check_notices', { domains });
+}
+
+export async function checkUptime(domains: string[]): Promise<UptimeStats[]> {
+  return invoke('check_uptime', { domains });
+}
+
+export async function uptimeStats(domains: string[]): Promise<UptimeStats[]> {
+  return invoke('uptime_stats', { domains });
+}
+
+export async function purgeUptimeHistory(domain?: string): Promise<void> {
+  await invoke('purge_uptime_history', { domain });
+}
+
+export async function watchlistList(): Promise<WatchItem[]> {
+  return invoke('watchlist_list');
+}
+
+export async function watchlistAdd(domain: string): Promise<WatchItem[]> {
+  return invoke('watchlist_add', { domain });
+}
+
+export async function watchlistRemove(domain: string): Promise<WatchItem[]> {
+  return invoke('watchlist_remove', { domain });
+}
+
+export async function watchlistCheck(): Promise<WatchItem[]> {
+  return invoke('watchlist_check');
+}
+
+export async function onWatchlistChanged(
+  cb: (changes: WatchChange[]) => void
+): Promise<() => void> {
+  if (isBrowserServer()) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<WatchChange[]>('watchlist://changed', (e) => cb(e.payload));
+}
+
+export async function favoritesList(refresh?: boolean): Promise<FavoriteView[]> {
+  return invoke('favorites_list', { refresh });
+}
+
+export async function favoritesAdd(instance: JsInstance, note?: string): Promise<Favorite[]> {
+  return invoke('favorites_add', { instance, note });
+}
+
+export async function favoritesRemove(domain: string): Promise<Favorite[]> {
+  return invoke('favorites_remove', { domain });
+}
+
+export async function favoritesReorder(domains: string[]): Promise<Favorite[]> {
+  return invoke('favorites_reorder', { domains });
+}
+
+export async function favoritesSetNote(domain: string, note: string): Promise<Favorite[]> {
+  return invoke('favorites_set_note', { domain, note });
+}
+
+export type ExportFormat = 'csv' | 'json' | 'markdown' | 'html';
+
+// `items` null exports the shortlist, labelled in the language last passed to setLocale;
+// resolves to the written path, or null if cancelled.
+// In the browser, the file is downloaded and its name returned.
+export async function exportResults(
+  items: JsInstance[] | null,
+  format: ExportFormat
+): Promise<string | null> {
+  if (!isBrowserServer()) return invoke('export_results', { items, format });
+  const file = await invoke<{ fileName: string; content: string }>('export_results', {
+    items,
+    format,
+  });
+  const url = URL.createObjectURL(new Blob([file.content]));
+  const link = document.createElement('a');
+  link.href = url;
+  link.download = file.fileName;
+  document.body.appendChild(link);
+  link.click();
+  link.remove();
+  URL.revokeObjectURL(url);
+  return file.fileName;
+}
+
+export type DeepLink =
+  | { kind: 'search'; params: FetchParams; preferences: Preferences }
+  | { kind: 'instance'; domain: string };
+
+// instances-finder:// link waiting to be applied; returned only once
+export async function takeDeepLink(): Promise<DeepLink | null> {
+  return invoke('take_deep_link');
+}
+
+export async function onDeepLink(
+  onOpen: () => void,
+  onInvalid: (error: string) => void
+): Promise<() => void> {
+  const { listen } = await import('@tauri-apps/api/event');
+  const unlistenOpen = await listen('deep-link://open', () => onOpen());
+  const unlistenInvalid = await listen<string>('deep-link://invalid', (e) => onInvalid(e.payload));
+  return () => {
+    unlistenOpen();
+    unlistenInvalid();
+  };
+}
+
+// Native menu selections (desktop app only)
+export type MenuAction =
+  | { action: 'preferences' }
+  | { action: 'refresh' }
+  | { action: 'export' }
+  | { action: 'manage_token' }
+  | { action: 'cache_cleared' }
+  | { action: 'shortcuts' }
+  | { action: 'open_instance'; domain: string };
+
+export async function onMenuAction(cb: (a: MenuAction) => void): Promise<() => void> {
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<MenuAction>('menu://action', (e) => cb(e.payload));
+}
@@ -9,19 +9,64 @@ export function scoreInstance(i: Instance, p: Preferences): number {
     const prefSize = p.size === 'small' ? 1 : p.size === 'medium' ? 2 : 3;
     s += 2 - Math.abs(prefSize - i.size);
   }
-  // Modération: bonus équilibré
+  // Modération: données réelles si sondées, sinon mode d'inscription
   if (p.moderation !== 'any') {
-    if (p.moderation === 'balanced' && i.signups === 'approval') s += 0.5;
-    if (p.moderation === 'open' && i.signups === 'open') s += 0.5;
-    if (p.moderation === 'strict' && i.signups === 'approval') s += 0.5;
+    const style = i.moderation?.style;
+    if (style && style !== 'unknown') {
+      if (style === p.moderation) s += 1;
+    } else {
+      if (p.moderation === 'balanced' && i.signups === 'approval') s += 0.5;
+      if (p.moderation === 'open' && i.signups === 'open') s += 0.5;
+      if (p.moderation === 'strict' && i.signups === 'approval') s += 0.5;
+    }
   }
   // Région
   if (p.region !== 'any' && i.region === p.region) s += 1;
+  // Accessibilité mesurée (textes alternatifs, hashtags CamelCase)
+  if (i.accessibility?.score != null) s += i.accessibility.score * 1.5;
+  // Versions vulnérables connues: plus un correctif critique tarde, plus la
+  // pénalité est forte
+  if (i.vulnerability) {
+    const v = i.vulnerability;
+    if (v.severity === 'critical') s -= (v.criticalUnpatchedDays ?? 0) > 90 ? 4 : 3;
+    else if (v.severity === 'high') s -= 1.5;
+    else s -= 0.5;
+  }
+  // Une fermeture annoncée l'emporte sur tout le reste
+  if (i.notice) s -= i.notice.kind === 'shutdown' ? 10 : i.notice.kind === 'read_only' ? 6 : 4;
+  // Activité: un grand serveur où personne ne se connecte n'est pas une communauté vivante
+  if (i.activity) {
+    if (i.activity.loginsPerUser != null && i.activity.loginsPerUser < 0.02) s -= 1;
+    if (i.activity.trendLabel === 'growing') s += 0.5;
+    if (i.activity.trendLabel === 'declining') s -= 0.5;
+  }
   // Disponibilité
   s += i.availability * 1.5;
   return s;
 }
 
+// Valeur du critère de tri choisi; les instances sans donnée passent en dernier
+function sortKey(i: Instance, sort: Preferences['sort']): number {
+  const a = i.activity;
+  const v =
+    sort === 'activity'
+      ? a?.weeklyLogins
+      : sort === 'growth'
+        ? a?.trend
+        : sort === 'peers'
+          ? a?.peers
+          : null;
+  return v ?? -Infinity;
+}
+
 export function rankInstances(list: Instance[], p: Preferences): Instance[] {
+  if (p.sort !== 'relevance') {
+    return [...list].sort((a, b) => {
+      const ka = sortKey(a, p.sort);
+      const kb = sortKey(b, p.sort);
+      if (ka !== kb) return kb > ka ? 1 : -1;
+      return scoreInstance(b, p) - scoreInstance(a, p);
+    });
+  }
   return [...list].sort((a, b) => scoreInstance(b, p) - scoreInstance(a, p));
 }
@@ -1,27 +1,186 @@
 // Auto-generated from src/locales/fr.json. Do not edit by hand.
 export type I18nKey =
   | 'app.title'
+  | 'deeplink.applied'
+  | 'deeplink.invalid'
+  | 'dialog.advisory_list'
+  | 'dialog.domain_lists'
+  | 'errors.advisories_older'
+  | 'errors.advisory_date'
+  | 'errors.advisory_severity'
+  | 'errors.advisory_versions'
+  | 'errors.bad_response'
+  | 'errors.browser_no_dialog_file'
+  | 'errors.browser_no_dialog_list'
+  | 'errors.certificate_expired'
+  | 'errors.certificate_invalid'
+  | 'errors.certificate_not_valid_yet'
+  | 'errors.certificate_revoked'
+  | 'errors.certificate_untrusted'
+  | 'errors.certificate_wrong_name'
+  | 'errors.connect'
+  | 'errors.data'
+  | 'errors.directory_status'
+  | 'errors.file_not_found'
+  | 'errors.file_too_large'
+  | 'errors.file_version'
+  | 'errors.http_status'
+  | 'errors.internal'
+  | 'errors.invalid_argument'
+  | 'errors.invalid_domain'
+  | 'errors.invalid_link'
+  | 'errors.invalid_value'
+  | 'errors.io'
+  | 'errors.keyring'
+  | 'errors.link_parts'
+  | 'errors.link_query'
+  | 'errors.link_too_long'
+  | 'errors.network'
+  | 'errors.no_instance_api'
+  | 'errors.no_nodeinfo'
+  | 'errors.no_resolve'
+  | 'errors.no_token'
+  | 'errors.nodeinfo_missing'
+  | 'errors.nodeinfo_schema'
+  | 'errors.not_favorite'
+  | 'errors.not_https'
+  | 'errors.not_local_file'
+  | 'errors.not_our_link'
+  | 'errors.permission_denied'
+  | 'errors.rate_limited'
+  | 'errors.rate_limited_retry'
+  | 'errors.repeated_parameter'
+  | 'errors.timed_out'
+  | 'errors.tls_handshake'
+  | 'errors.unknown_command'
+  | 'errors.unknown_link'
+  | 'errors.unknown_parameter'
+  | 'errors.unsupported_file'
+  | 'evaluate.done'
+  | 'evaluate.error'
+  | 'evaluate.help'
+  | 'evaluate.input'
+  | 'evaluate.invalid'
+  | 'evaluate.list'
+  | 'evaluate.list_done'
+  | 'evaluate.list_error'
+  | 'evaluate.list_failure'
+  | 'evaluate.list_failures'
+  | 'evaluate.list_file'
+  | 'evaluate.list_help'
+  | 'evaluate.list_progress'
+  | 'evaluate.list_running'
+  | 'evaluate.list_submit'
+  | 'evaluate.running'
+  | 'evaluate.submit'
+  | 'evaluate.title'
+  | 'export.caption'
+  | 'export.col_description'
+  | 'export.col_note'
+  | 'export.col_rank'
+  | 'export.col_security'
+  | 'export.col_users'
+  | 'export.col_vulnerabilities'
+  | 'export.done'
+  | 'export.error'
+  | 'export.format'
+  | 'export.format_csv'
+  | 'export.format_html'
+  | 'export.format_json'
+  | 'export.format_markdown'
+  | 'export.results'
+  | 'export.shortlist'
+  | 'export.signups_closed'
+  | 'export.title_results'
+  | 'export.title_shortlist'
   | 'header.en'
   | 'header.expert'
   | 'header.fr'
   | 'header.language'
   | 'header.preferences'
+  | 'menu.about'
+  | 'menu.about_body'
+  | 'menu.about_no_data'
+  | 'menu.about_title'
+  | 'menu.age_days'
+  | 'menu.age_hours'
+  | 'menu.age_minutes'
+  | 'menu.cache_cleared'
+  | 'menu.clear_cache'
+  | 'menu.clear_cache_error'
+  | 'menu.export'
+  | 'menu.file'
+  | 'menu.help'
+  | 'menu.preferences'
+  | 'menu.quit'
+  | 'menu.recent'
+  | 'menu.recent_all'
+  | 'menu.recent_clear'
+  | 'menu.recent_empty'
+  | 'menu.refresh'
+  | 'menu.shortcuts'
+  | 'menu.shortlist'
+  | 'menu.shortlist_empty'
+  | 'menu.token'
+  | 'menu.view'
+  | 'menu.watchlist'
+  | 'menu.watchlist_empty'
+  | 'notify.watch_body'
+  | 'notify.watch_title'
   | 'prefs.accessibility'
+  | 'prefs.advisories_bundled'
+  | 'prefs.advisories_import'
+  | 'prefs.advisories_import_done'
+  | 'prefs.advisories_import_error'
+  | 'prefs.advisories_imported'
   | 'prefs.braille_refresh'
+  | 'prefs.check_notices'
+  | 'prefs.check_notices_help'
   | 'prefs.close'
+  | 'prefs.data'
   | 'prefs.expert_mode'
   | 'prefs.general'
+  | 'prefs.shortcut_clear_cache'
   | 'prefs.shortcut_close_prefs'
   | 'prefs.shortcut_copy_shortcut'
   | 'prefs.shortcut_enter_open'
+  | 'prefs.shortcut_export'
+  | 'prefs.shortcut_help'
   | 'prefs.shortcut_prefs'
+  | 'prefs.shortcut_quit'
   | 'prefs.shortcut_refresh'
   | 'prefs.shortcut_tab_copy'
+  | 'prefs.shortcut_token'
   | 'prefs.shortcuts'
   | 'prefs.title'
   | 'prefs.ui_language'
+  | 'prefs.uptime_help'
+  | 'prefs.uptime_purge'
+  | 'prefs.uptime_purge_error'
+  | 'prefs.uptime_purged'
+  | 'preview.close'
+  | 'preview.cw'
+  | 'preview.done'
+  | 'preview.error'
+  | 'preview.latency'
+  | 'preview.link'
+  | 'preview.loading'
+  | 'preview.media_audio'
+  | 'preview.media_gif'
+  | 'preview.media_image'
+  | 'preview.media_no_description'
+  | 'preview.media_video'
+  | 'preview.posts'
+  | 'preview.private'
+  | 'preview.sensitive'
+  | 'preview.tag_uses'
+  | 'preview.title'
+  | 'preview.trends'
+  | 'preview.uptime'
+  | 'results.accessibility'
   | 'results.actions_label'
   | 'results.approval'
+  | 'results.closed'
   | 'results.col_actions'
   | 'results.col_domain'
   | 'results.col_languages'
@@ -34,17 +193,69 @@ export type I18nKey =
   | 'results.hint_open'
   | 'results.list_label'
   | 'results.next'
+  | 'results.notice_migration'
+  | 'results.notice_read_only'
+  | 'results.notice_shutdown'
   | 'results.open'
   | 'results.openBrowser'
+  | 'results.peers'
   | 'results.prev'
+  | 'results.preview'
+  | 'results.privacy_cdn'
+  | 'results.privacy_ok'
+  | 'results.privacy_third_party'
+  | 'results.security'
+  | 'results.shortlist'
   | 'results.skip_table'
   | 'results.table_caption'
   | 'results.title'
+  | 'results.trend_declining'
+  | 'results.trend_growing'
+  | 'results.trend_stable'
+  | 'results.vulnerable'
+  | 'results.vulnerable_critical'
+  | 'results.watch'
+  | 'server.expected_json'
+  | 'server.forbidden'
+  | 'server.invalid_secret'
+  | 'server.method_not_allowed'
+  | 'server.no_session'
+  | 'server.not_found'
+  | 'server.unreadable_body'
+  | 'shortlist.added'
+  | 'shortlist.change'
+  | 'shortlist.changed'
+  | 'shortlist.check'
+  | 'shortlist.check_error'
+  | 'shortlist.checked'
+  | 'shortlist.checking'
+  | 'shortlist.empty'
+  | 'shortlist.field_description'
+  | 'shortlist.field_languages'
+  | 'shortlist.field_moderation'
+  | 'shortlist.field_notice'
+  | 'shortlist.field_security'
+  | 'shortlist.field_signups'
+  | 'shortlist.field_sizeLabel'
+  | 'shortlist.field_software'
+  | 'shortlist.field_vulnerability'
+  | 'shortlist.move_down'
+  | 'shortlist.move_up'
+  | 'shortlist.moved'
+  | 'shortlist.none'
+  | 'shortlist.note'
+  | 'shortlist.note_saved'
+  | 'shortlist.remove'
+  | 'shortlist.removed'
+  | 'shortlist.title'
   | 'status.done'
   | 'status.error'
   | 'status.loading'
   | 'status.needs_token'
+  | 'token.cancel'
   | 'token.desc'
+  | 'token.forget'
+  | 'token.forget_error'
   | 'token.get'
   | 'token.help'
   | 'token.input'
@@ -56,6 +267,9 @@ export type I18nKey =
   | 'token.testing'
   | 'token.title'
   | 'token.valid'
+  | 'watchlist.added'
+  | 'watchlist.changed'
+  | 'watchlist.removed'
   | 'wizard.apply'
   | 'wizard.clear_all'
   | 'wizard.indifferent'
@@ -64,17 +278,24 @@ export type I18nKey =
   | 'wizard.languages_hint'
   | 'wizard.moderation'
   | 'wizard.moderation_balanced'
+  | 'wizard.moderation_help'
   | 'wizard.moderation_open'
   | 'wizard.moderation_strict'
   | 'wizard.not_selected'
   | 'wizard.nsfw'
   | 'wizard.nsfw_allowed'
   | 'wizard.nsfw_limited'
+  | 'wizard.privacy'
+  | 'wizard.privacy_no_cdn'
+  | 'wizard.privacy_no_third_party'
+  | 'wizard.privacy_strict'
   | 'wizard.region'
   | 'wizard.region_eu'
   | 'wizard.region_experimental'
   | 'wizard.region_na'
   | 'wizard.region_other'
+  | 'wizard.security'
+  | 'wizard.security_min'
   | 'wizard.select_all'
   | 'wizard.selected'
   | 'wizard.signups'
@@ -84,4 +305,13 @@ export type I18nKey =
   | 'wizard.size_large'
   | 'wizard.size_medium'
   | 'wizard.size_small'
-  | 'wizard.title';
+  | 'wizard.software'
+  | 'wizard.sort'
+  | 'wizard.sort_activity'
+  | 'wizard.sort_growth'
+  | 'wizard.sort_peers'
+  | 'wizard.sort_relevance'
+  | 'wizard.title'
+  | 'wizard.vulnerable'
+  | 'wizard.vulnerable_hide'
+  | 'wizard.vulnerable_rank';
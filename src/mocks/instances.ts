@@ -7,7 +7,7 @@ export const mockInstances: Instance[] = [
     languages: ['en'],
     signups: 'open',
     size: 3,
-    sizeLabel: 'Grande',
+    sizeLabel: 'Large',
     region: 'eu',
     availability: 0.997,
   },
@@ -17,7 +17,7 @@ export const mockInstances: Instance[] = [
     languages: ['en', 'fr'],
     signups: 'approval',
     size: 2,
-    sizeLabel: 'Moyenne',
+    sizeLabel: 'Medium',
     region: 'eu',
     availability: 0.999,
   },
@@ -27,7 +27,7 @@ export const mockInstances: Instance[] = [
     languages: ['fr'],
     signups: 'open',
     size: 2,
-    sizeLabel: 'Moyenne',
+    sizeLabel: 'Medium',
     region: 'eu',
     availability: 0.995,
   },
@@ -37,7 +37,7 @@ export const mockInstances: Instance[] = [
     languages: ['en'],
     signups: 'approval',
     size: 2,
-    sizeLabel: 'Moyenne',
+    sizeLabel: 'Medium',
     region: 'na',
     availability: 0.998,
   },
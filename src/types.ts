@@ -5,15 +5,68 @@ export type Preferences = {
   signups: 'any' | 'open' | 'approval';
   region: 'any' | 'eu' | 'na' | 'other';
   nsfw: 'any' | 'allowed' | 'limited';
+  software: 'any' | Software;
+  security: 'any' | 'A' | 'B' | 'C';
+  privacy: 'any' | 'no_cdn' | 'no_third_party' | 'strict';
+  vulnerable: 'any' | 'hide';
+  sort: 'relevance' | 'activity' | 'growth' | 'peers';
+};
+
+export type Software =
+  | 'mastodon'
+  | 'glitch-soc'
+  | 'hometown'
+  | 'pleroma'
+  | 'akkoma'
+  | 'gotosocial'
+  | 'misskey';
+
+export type InstanceModeration = {
+  public: boolean;
+  rulesCount: number;
+  suspended: number;
+  silenced: number;
+  style: 'open' | 'balanced' | 'strict' | 'unknown';
+};
+
+export type InstanceAccessibility = {
+  timelinePublic: boolean;
+  altTextRatio: number | null;
+  camelCaseRatio: number | null;
+  mentionsAccessibility: boolean;
+  score: number | null; // 0..1
 };
 
 export type Instance = {
   domain: string;
   description: string;
   languages: string[];
-  signups: 'open' | 'approval';
+  signups: 'open' | 'approval' | 'closed';
   size: 1 | 2 | 3; // 1 small, 2 medium, 3 large
   sizeLabel: string;
   region: 'eu' | 'na' | 'other';
   availability: number; // 0..1
+  moderation?: InstanceModeration; // only when probed
+  accessibility?: InstanceAccessibility; // only when probed
+  software?: { name: string; version: string }; // from NodeInfo, when probed
+  users?: number; // registered accounts, when known
+  activeMonth?: number; // accounts active in the last 30 days, when known
+  activity?: {
+    peers: number | null;
+    weeklyLogins: number | null;
+    trend: number | null; // 0.25 = +25 % logins over 4 weeks
+    trendLabel: 'growing' | 'stable' | 'declining' | 'unknown';
+    loginsPerUser: number | null;
+  }; // when probed
+  // Shutdown, migration or read-only notice announced by the instance
+  notice?: { kind: 'shutdown' | 'migration' | 'read_only'; excerpt: string };
+  vulnerability?: {
+    version: string;
+    severity: 'low' | 'medium' | 'high' | 'critical';
+    criticalUnpatchedDays: number | null;
+    advisories: { id: string; title: string }[];
+  }; // known unpatched advisories
+  privacy?: { cdn: string | null; thirdPartyOrigins: string[] }; // when audited
+  // Transport security grade, when audited
+  security?: { grade: 'A' | 'B' | 'C' | 'D' | 'E' | 'F'; daysUntilExpiry: number | null };
 };
@@ -4,7 +4,9 @@ use std::env;
 use std::path::Path;
 use std::process::{Command, Stdio};
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use instances_finder_mock_api as mock_api;
 
 #[derive(Parser)]
 #[command(
@@ -80,6 +82,26 @@ enum Cmd {
         #[command(subcommand)]
         action: ReleaseAction,
     },
+    /// Serve a fake instances.social API on localhost
+    MockApi {
+        #[arg(long, default_value_t = 8787)]
+        port: u16,
+        /// Answer every request with this HTTP error
+        #[arg(long, value_parser = ["401", "429", "500"])]
+        fail: Option<String>,
+        /// Retry-After seconds sent with --fail 429
+        #[arg(long, default_value_t = 30)]
+        retry_after: u64,
+        /// Delay each answer (milliseconds)
+        #[arg(long, default_value_t = 0)]
+        delay_ms: u64,
+        /// Instances per page of instances/list
+        #[arg(long)]
+        page_size: Option<usize>,
+        /// Bearer token to accept
+        #[arg(long, default_value = mock_api::DEFAULT_TOKEN)]
+        token: String,
+    },
 }
 
 #[derive(Subcommand, Clone, Copy)]
@@ -141,6 +163,21 @@ fn main() -> Result<()> {
         Cmd::Clean => clean_cmd(),
         Cmd::Setup { xtask_release } => setup_cmd(xtask_release),
         Cmd::Release { action } => release_cmd(action),
+        Cmd::MockApi {
+            port,
+            fail,
+            retry_after,
+            delay_ms,
+            page_size,
+            token,
+        } => mock_api_cmd(
+            port,
+            fail.as_deref(),
+            retry_after,
+            delay_ms,
+            page_size,
+            &token,
+        ),
     }
 }
 
@@ -449,6 +486,7 @@ fn check_cmd() -> Result<()> {
             "clippy",
             "--manifest-path",
             "src-tauri/Cargo.toml",
+            "--workspace",
             "--",
             "-D",
             "warnings",
@@ -479,6 +517,7 @@ fn check_cmd_parallel() -> Result<()> {
                 "clippy",
                 "--manifest-path",
                 "src-tauri/Cargo.toml",
+                "--workspace",
                 "--",
                 "-D",
                 "warnings",
@@ -611,6 +650,7 @@ fn ci_checks_cmd() -> Result<()> {
             "clippy",
             "--manifest-path",
             "src-tauri/Cargo.toml",
+            "--workspace",
             "--",
             "-D",
             "warnings",
@@ -627,6 +667,7 @@ fn ci_checks_cmd() -> Result<()> {
                 "fmt",
                 "--manifest-path",
                 "src-tauri/Cargo.toml",
+                "--all",
                 "--",
                 "--check",
             ])
@@ -739,6 +780,7 @@ fn ci_checks_cmd_parallel() -> Result<()> {
             "clippy".into(),
             "--manifest-path".into(),
             "src-tauri/Cargo.toml".into(),
+            "--workspace".into(),
             "--".into(),
             "-D".into(),
             "warnings".into(),
@@ -754,6 +796,7 @@ fn ci_checks_cmd_parallel() -> Result<()> {
                 "fmt".into(),
                 "--manifest-path".into(),
                 "src-tauri/Cargo.toml".into(),
+                "--all".into(),
                 "--".into(),
                 "--check".into(),
             ],
@@ -912,6 +955,7 @@ fn fmt_check_cmd(which: FmtWhich) -> Result<()> {
                     "fmt",
                     "--manifest-path",
                     "src-tauri/Cargo.toml",
+                    "--all",
                     "--",
                     "--check",
                 ])
@@ -935,6 +979,7 @@ fn clippy_cmd() -> Result<()> {
             "clippy",
             "--manifest-path",
             "src-tauri/Cargo.toml",
+            "--workspace",
             "--",
             "-D",
             "warnings",
@@ -1010,3 +1055,36 @@ fn bench_cmd() -> Result<()> {
 
     Ok(())
 }
+
+fn mock_api_cmd(
+    port: u16,
+    fail: Option<&str>,
+    retry_after: u64,
+    delay_ms: u64,
+    page_size: Option<usize>,
+    token: &str,
+) -> Result<()> {
+    let mock = mock_api::MockDirectory::start_on(port)
+        .with_context(|| format!("cannot listen on 127.0.0.1:{port}"))?;
+    mock.set_token(token);
+    mock.set_delay(Duration::from_millis(delay_ms));
+    if let Some(size) = page_size {
+        mock.set_page_size(size);
+    }
+    mock.set_failure(match fail {
+        Some("401") => Some(mock_api::Failure::Unauthorized),
+        Some("429") => Some(mock_api::Failure::RateLimited { retry_after }),
+        Some("500") => Some(mock_api::Failure::ServerError),
+        _ => None,
+    });
+    println!("[mock-api] Serving {}", mock.base_url());
+    println!("[mock-api] Token: {token}");
+    println!(
+        "[mock-api] Use it with: INSTANCES_FINDER_API_URL={} make dev",
+        mock.base_url()
+    );
+    println!("[mock-api] Ctrl+C to stop");
+    loop {
+        thread::sleep(Duration::from_secs(3600));
+    }
+}